@@ -0,0 +1,423 @@
+use crate::math::shapes::{AnyShape, Ellipse, Polygon, Rectangle, Triangle};
+use crate::math::Point;
+use crate::optimizer::Candidate;
+use image::Rgba;
+use serde_json::{json, Value};
+use std::{error, fmt};
+
+// Identifiers of the shape types used by the original Geometrize tools.
+const RECTANGLE: u64 = 0;
+const ROTATED_RECTANGLE: u64 = 1;
+const TRIANGLE: u64 = 2;
+const ELLIPSE: u64 = 3;
+const ROTATED_ELLIPSE: u64 = 4;
+const CIRCLE: u64 = 5;
+
+/// Polygons don't exist in the original Geometrize tools, this identifier is
+/// an extension of libgeometrize.
+const POLYGON: u64 = 9;
+
+/// Defines the errors which can happen while reading a JSON shape list.
+#[derive(Debug)]
+pub enum JsonError {
+    /// The document isn't valid JSON.
+    Syntax(serde_json::Error),
+
+    /// The shape at the given index doesn't follow the expected layout.
+    Malformed(usize, String),
+
+    /// The shape at the given index has a type not supported by libgeometrize.
+    UnsupportedShape(usize, u64),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Syntax(error) => write!(f, "invalid JSON document: {}", error),
+            JsonError::Malformed(index, reason) => {
+                write!(f, "malformed shape at index {}: {}", index, reason)
+            }
+            JsonError::UnsupportedShape(index, kind) => {
+                write!(f, "unsupported shape type {} at index {}", kind, index)
+            }
+        }
+    }
+}
+
+impl error::Error for JsonError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            JsonError::Syntax(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(error: serde_json::Error) -> Self {
+        JsonError::Syntax(error)
+    }
+}
+
+/// Writes a list of shapes as a JSON document following the layout of the
+/// original Geometrize tools: an array of objects made of the `type` of the
+/// shape, its parameters in `data`, its RGBA `color` and its `score`.
+///
+/// Angles are written in degrees and rotated rectangles are described by their
+/// corners before being rotated around their center, as Geometrize does.
+///
+/// This function is only available with the `json` feature, enabled by default.
+///
+/// # Example
+///
+/// ```
+/// use image::Rgba;
+/// use libgeometrize::formats::{from_json, to_json};
+/// use libgeometrize::math::shapes::Ellipse;
+/// use libgeometrize::optimizer::Candidate;
+///
+/// let shapes = vec![Candidate {
+///     shape: Ellipse::new().u(16.0).v(8.0).a(4.0).b(2.0).build().into(),
+///     color: Rgba([255, 0, 0, 128]),
+///     score: 0.25,
+/// }];
+///
+/// let json = to_json(&shapes);
+/// assert_eq!(1, from_json(&json).unwrap().len());
+/// ```
+pub fn to_json(shapes: &[Candidate<AnyShape>]) -> String {
+    let shapes: Vec<Value> = shapes
+        .iter()
+        .map(|candidate| {
+            let (kind, data) = raw_shape_data(&candidate.shape);
+            json!({
+                "type": kind,
+                "data": data,
+                "color": candidate.color.0,
+                "score": candidate.score,
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&shapes).unwrap()
+}
+
+/// Reads a list of shapes from a JSON document following the layout of the
+/// original Geometrize tools. Circles are read as ellipses, while lines,
+/// quadratic Bézier curves and polylines aren't supported.
+///
+/// This function is only available with the `json` feature, enabled by default.
+pub fn from_json(json: &str) -> Result<Vec<Candidate<AnyShape>>, JsonError> {
+    let document: Value = serde_json::from_str(json)?;
+    let shapes = document
+        .as_array()
+        .ok_or_else(|| JsonError::Malformed(0, "the document is not an array".to_string()))?;
+
+    shapes
+        .iter()
+        .enumerate()
+        .map(|(index, shape)| read_shape(index, shape))
+        .collect()
+}
+
+/// Returns the Geometrize type identifier and the parameters of a shape.
+fn raw_shape_data(shape: &AnyShape) -> (u64, Vec<f64>) {
+    match shape {
+        AnyShape::Ellipse(ellipse) => {
+            let center = ellipse.center();
+            let mut data = vec![center.x, center.y, ellipse.a(), ellipse.b()];
+
+            match ellipse.angle() {
+                Some(angle) => {
+                    data.push(angle.to_degrees());
+                    (ROTATED_ELLIPSE, data)
+                }
+                None => (ELLIPSE, data),
+            }
+        }
+        AnyShape::Rectangle(rect) if rect.is_rotated() => {
+            // Geometrize rotates rectangles around their center
+            let (width, height) = (rect.width(), rect.height());
+            let (sin, cos) = rect.angle.sin_cos();
+            let cx = rect.origin.x + width / 2.0 * cos - height / 2.0 * sin;
+            let cy = rect.origin.y + width / 2.0 * sin + height / 2.0 * cos;
+
+            (
+                ROTATED_RECTANGLE,
+                vec![
+                    cx - width / 2.0,
+                    cy - height / 2.0,
+                    cx + width / 2.0,
+                    cy + height / 2.0,
+                    rect.angle.to_degrees(),
+                ],
+            )
+        }
+        AnyShape::Rectangle(rect) => (
+            RECTANGLE,
+            vec![
+                rect.origin.x,
+                rect.origin.y,
+                rect.origin.x + rect.width(),
+                rect.origin.y + rect.height(),
+            ],
+        ),
+        AnyShape::Triangle(triangle) => (TRIANGLE, flatten(triangle.vertices())),
+        AnyShape::Polygon(polygon) => (POLYGON, flatten(polygon.vertices())),
+    }
+}
+
+/// Reads a single shape of a JSON shape list.
+fn read_shape(index: usize, shape: &Value) -> Result<Candidate<AnyShape>, JsonError> {
+    let malformed = |reason: &str| JsonError::Malformed(index, reason.to_string());
+
+    let kind = shape["type"]
+        .as_u64()
+        .ok_or_else(|| malformed("missing or invalid `type`"))?;
+    let data = shape["data"]
+        .as_array()
+        .and_then(|data| data.iter().map(Value::as_f64).collect::<Option<Vec<f64>>>())
+        .ok_or_else(|| malformed("missing or invalid `data`"))?;
+    let color = shape["color"]
+        .as_array()
+        .filter(|color| color.len() == 4)
+        .and_then(|color| {
+            color
+                .iter()
+                .map(|channel| channel.as_u64().filter(|c| *c <= 255).map(|c| c as u8))
+                .collect::<Option<Vec<u8>>>()
+        })
+        .ok_or_else(|| malformed("missing or invalid `color`"))?;
+    let score = shape["score"]
+        .as_f64()
+        .ok_or_else(|| malformed("missing or invalid `score`"))?;
+
+    let expected = match kind {
+        RECTANGLE | ELLIPSE => Some(4),
+        ROTATED_RECTANGLE | ROTATED_ELLIPSE => Some(5),
+        TRIANGLE => Some(6),
+        CIRCLE => Some(3),
+        POLYGON => None,
+        _ => return Err(JsonError::UnsupportedShape(index, kind)),
+    };
+    if expected.map_or(data.len() % 2 != 0 || data.len() < 6, |len| {
+        data.len() != len
+    }) {
+        return Err(malformed("unexpected number of parameters in `data`"));
+    }
+    if data.iter().any(|value| !value.is_finite()) {
+        return Err(malformed("non-finite parameter in `data`"));
+    }
+
+    // The sizes and radii are computed, so they can overflow even though all
+    // the parameters are finite
+    let sizes = match kind {
+        RECTANGLE | ROTATED_RECTANGLE => {
+            vec![(data[2] - data[0]).abs(), (data[3] - data[1]).abs()]
+        }
+        ELLIPSE | ROTATED_ELLIPSE => vec![data[2], data[3]],
+        CIRCLE => vec![data[2]],
+        _ => Vec::new(),
+    };
+    if sizes.iter().any(|size| !(size.is_finite() && *size > 0.0)) {
+        return Err(malformed(
+            "sizes and radii in `data` must be finite and positive",
+        ));
+    }
+
+    let shape: AnyShape = match kind {
+        RECTANGLE => Rectangle::new()
+            .origin(data[0].min(data[2]), data[1].min(data[3]))
+            .aspect((data[2] - data[0]).abs(), (data[3] - data[1]).abs())
+            .build()
+            .into(),
+        ROTATED_RECTANGLE => {
+            let (width, height) = ((data[2] - data[0]).abs(), (data[3] - data[1]).abs());
+            let (cx, cy) = ((data[0] + data[2]) / 2.0, (data[1] + data[3]) / 2.0);
+            let angle = data[4].to_radians();
+            let (sin, cos) = angle.sin_cos();
+
+            Rectangle::new()
+                .origin(
+                    cx - width / 2.0 * cos + height / 2.0 * sin,
+                    cy - width / 2.0 * sin - height / 2.0 * cos,
+                )
+                .aspect(width, height)
+                .angle(angle)
+                .build()
+                .into()
+        }
+        TRIANGLE => Triangle::new(
+            Point::new(data[0], data[1]),
+            Point::new(data[2], data[3]),
+            Point::new(data[4], data[5]),
+        )
+        .into(),
+        ELLIPSE | ROTATED_ELLIPSE => {
+            let builder = Ellipse::new().u(data[0]).v(data[1]).a(data[2]).b(data[3]);
+
+            match kind {
+                ROTATED_ELLIPSE => builder.angle(data[4].to_radians()).build().into(),
+                _ => builder.build().into(),
+            }
+        }
+        CIRCLE => Ellipse::new()
+            .u(data[0])
+            .v(data[1])
+            .a(data[2])
+            .b(data[2])
+            .build()
+            .into(),
+        _ => Polygon::from(
+            data.chunks_exact(2)
+                .map(|p| Point::new(p[0], p[1]))
+                .collect::<Vec<_>>(),
+        )
+        .into(),
+    };
+
+    Ok(Candidate {
+        shape,
+        color: Rgba([color[0], color[1], color[2], color[3]]),
+        score,
+    })
+}
+
+/// Flattens a list of points into the list of their coordinates.
+fn flatten(points: &[Point]) -> Vec<f64> {
+    points.iter().flat_map(|p| vec![p.x, p.y]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::shapes::Shape;
+
+    #[test]
+    fn it_reads_a_geometrize_shape_list() {
+        let json = r#"[
+            {"type": 0, "data": [0, 0, 10, 5], "color": [10, 20, 30, 255], "score": 0.5},
+            {"type": 4, "data": [5, 5, 3, 2, 90], "color": [0, 0, 0, 128], "score": 0.4},
+            {"type": 5, "data": [8, 8, 2], "color": [1, 1, 1, 128], "score": 0.3}
+        ]"#;
+
+        let shapes = from_json(json).unwrap();
+        assert_eq!(3, shapes.len());
+
+        match &shapes[0].shape {
+            AnyShape::Rectangle(rect) => {
+                assert_eq!((10.0, 5.0), (rect.width(), rect.height()));
+                assert!(!rect.is_rotated());
+            }
+            shape => panic!("unexpected shape {:?}", shape),
+        }
+        match &shapes[1].shape {
+            AnyShape::Ellipse(ellipse) => {
+                assert!((ellipse.angle().unwrap() - std::f64::consts::FRAC_PI_2).abs() < 1e-12)
+            }
+            shape => panic!("unexpected shape {:?}", shape),
+        }
+        match &shapes[2].shape {
+            AnyShape::Ellipse(ellipse) => assert!(ellipse.is_circle() && !ellipse.is_rotated()),
+            shape => panic!("unexpected shape {:?}", shape),
+        }
+        assert_eq!(Rgba([0, 0, 0, 128]), shapes[1].color);
+        assert_eq!(0.3, shapes[2].score);
+    }
+
+    #[test]
+    fn it_writes_rotated_rectangles_around_their_center() {
+        let shapes = vec![Candidate {
+            shape: Rectangle::new()
+                .origin(10.0, 10.0)
+                .aspect(4.0, 2.0)
+                .angle(std::f64::consts::PI)
+                .build()
+                .into(),
+            color: Rgba([0, 0, 0, 255]),
+            score: 0.0,
+        }];
+
+        let document: Value = serde_json::from_str(&to_json(&shapes)).unwrap();
+        let data: Vec<f64> = document[0]["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_f64().unwrap())
+            .collect();
+
+        assert_eq!(1, document[0]["type"]);
+        for (expected, value) in [6.0, 8.0, 10.0, 10.0, 180.0].iter().zip(&data) {
+            assert!((expected - value).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn it_round_trips_every_shape() {
+        use crate::math::shapes::ShapeKind;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let shapes: Vec<Candidate<AnyShape>> = [
+            ShapeKind::Ellipse,
+            ShapeKind::RotatedEllipse,
+            ShapeKind::Rectangle,
+            ShapeKind::RotatedRectangle,
+            ShapeKind::Triangle,
+            ShapeKind::Polygon(6),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(idx, kind)| Candidate {
+            shape: kind.random(&mut rng, 128, 128),
+            color: Rgba([idx as u8, 2, 3, 128]),
+            score: idx as f64 / 10.0,
+        })
+        .collect();
+
+        let read = from_json(&to_json(&shapes)).unwrap();
+        assert_eq!(shapes.len(), read.len());
+        for (a, b) in shapes.iter().zip(&read) {
+            assert_eq!(a.color, b.color);
+            assert_eq!(a.score, b.score);
+            assert_eq!(raw_shape_data(&a.shape).0, raw_shape_data(&b.shape).0);
+            assert_eq!(a.shape.rasterize(128, 128), b.shape.rasterize(128, 128));
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_documents() {
+        assert!(matches!(from_json("{"), Err(JsonError::Syntax(_))));
+        assert!(matches!(
+            from_json(r#"[{"type": 6, "data": [0, 0, 1, 1], "color": [0, 0, 0, 0], "score": 0}]"#),
+            Err(JsonError::UnsupportedShape(0, 6))
+        ));
+        assert!(matches!(
+            from_json(r#"[{"type": 2, "data": [0, 0, 1, 1], "color": [0, 0, 0, 0], "score": 0}]"#),
+            Err(JsonError::Malformed(0, _))
+        ));
+        assert!(matches!(
+            from_json(
+                r#"[{"type": 3, "data": [0, 0, 1, 1], "color": [0, 0, 0, 256], "score": 0}]"#
+            ),
+            Err(JsonError::Malformed(0, _))
+        ));
+    }
+
+    #[test]
+    fn it_rejects_degenerate_shapes() {
+        let documents = [
+            r#"[{"type": 5, "data": [8, 8, 0], "color": [0, 0, 0, 0], "score": 0}]"#,
+            r#"[{"type": 3, "data": [8, 8, 4, -2], "color": [0, 0, 0, 0], "score": 0}]"#,
+            r#"[{"type": 0, "data": [0, 0, 10, 0], "color": [0, 0, 0, 0], "score": 0}]"#,
+            r#"[{"type": 1, "data": [-1e308, 0, 1e308, 5, 0], "color": [0, 0, 0, 0], "score": 0}]"#,
+        ];
+
+        for document in &documents {
+            assert!(matches!(
+                from_json(document),
+                Err(JsonError::Malformed(0, _))
+            ));
+        }
+    }
+}
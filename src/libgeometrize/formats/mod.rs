@@ -0,0 +1,7 @@
+#[cfg(feature = "json")]
+mod json;
+mod svg;
+
+#[cfg(feature = "json")]
+pub use json::{from_json, to_json, JsonError};
+pub use svg::{to_svg, SvgElement};
@@ -0,0 +1,250 @@
+use crate::math::shapes::{AnyShape, Ellipse, Polygon, Rectangle, Triangle};
+use crate::math::Point;
+use image::Rgba;
+use std::fmt::Write;
+
+/// Defines a shape which can be written as an element of a SVG document.
+pub trait SvgElement {
+    /// Returns the SVG element drawing the current shape filled with a color.
+    fn to_svg_element(&self, color: Rgba<u8>) -> String;
+}
+
+/// Writes an ordered list of shapes along with their colors as a SVG document
+/// of the given dimensions, on top of a background color.
+///
+/// # Example
+///
+/// ```
+/// use image::Rgba;
+/// use libgeometrize::formats::to_svg;
+/// use libgeometrize::math::shapes::Ellipse;
+///
+/// let ellipse = Ellipse::new().u(16.0).v(16.0).a(8.0).b(4.0).build();
+/// let shapes = vec![(ellipse, Rgba([255, 0, 0, 128]))];
+///
+/// let svg = to_svg(32, 32, Rgba([255, 255, 255, 255]), shapes.iter().map(|(s, c)| (s, *c)));
+/// assert!(svg.contains(r#"<ellipse cx="16" cy="16" rx="8" ry="4""#));
+/// ```
+pub fn to_svg<'a, S, I>(width: u32, height: u32, background: Rgba<u8>, shapes: I) -> String
+where
+    S: SvgElement + 'a,
+    I: IntoIterator<Item = (&'a S, Rgba<u8>)>,
+{
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<rect x="0" y="0" width="{}" height="{}" {}/>"#,
+        width,
+        height,
+        fill(background)
+    )
+    .unwrap();
+    for (shape, color) in shapes {
+        writeln!(svg, "{}", shape.to_svg_element(color)).unwrap();
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+impl SvgElement for Ellipse {
+    fn to_svg_element(&self, color: Rgba<u8>) -> String {
+        let center = self.center();
+        let transform = match self.angle() {
+            Some(angle) => format!(
+                r#" transform="rotate({} {} {})""#,
+                number(angle.to_degrees()),
+                number(center.x),
+                number(center.y)
+            ),
+            None => String::new(),
+        };
+
+        format!(
+            r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}"{} {}/>"#,
+            number(center.x),
+            number(center.y),
+            number(self.a()),
+            number(self.b()),
+            transform,
+            fill(color)
+        )
+    }
+}
+
+impl SvgElement for Rectangle {
+    fn to_svg_element(&self, color: Rgba<u8>) -> String {
+        if self.is_rotated() {
+            // The rectangle is rotated around its origin
+            format!(
+                r#"<rect x="0" y="0" width="{}" height="{}" transform="translate({} {}) rotate({})" {}/>"#,
+                number(self.width()),
+                number(self.height()),
+                number(self.origin.x),
+                number(self.origin.y),
+                number(self.angle.to_degrees()),
+                fill(color)
+            )
+        } else {
+            format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                number(self.origin.x),
+                number(self.origin.y),
+                number(self.width()),
+                number(self.height()),
+                fill(color)
+            )
+        }
+    }
+}
+
+impl SvgElement for Triangle {
+    fn to_svg_element(&self, color: Rgba<u8>) -> String {
+        polygon_element(self.vertices(), color)
+    }
+}
+
+impl SvgElement for Polygon {
+    fn to_svg_element(&self, color: Rgba<u8>) -> String {
+        polygon_element(self.vertices(), color)
+    }
+}
+
+impl SvgElement for AnyShape {
+    fn to_svg_element(&self, color: Rgba<u8>) -> String {
+        match self {
+            AnyShape::Ellipse(shape) => shape.to_svg_element(color),
+            AnyShape::Rectangle(shape) => shape.to_svg_element(color),
+            AnyShape::Triangle(shape) => shape.to_svg_element(color),
+            AnyShape::Polygon(shape) => shape.to_svg_element(color),
+        }
+    }
+}
+
+/// Returns a `<polygon>` element going through the given vertices.
+fn polygon_element(vertices: &[Point], color: Rgba<u8>) -> String {
+    let points: Vec<String> = vertices
+        .iter()
+        .map(|p| format!("{},{}", number(p.x), number(p.y)))
+        .collect();
+
+    format!(
+        r#"<polygon points="{}" {}/>"#,
+        points.join(" "),
+        fill(color)
+    )
+}
+
+/// Returns the attributes filling an element with a color.
+fn fill(color: Rgba<u8>) -> String {
+    format!(
+        r#"fill="rgb({},{},{})" fill-opacity="{}""#,
+        color[0],
+        color[1],
+        color[2],
+        number(color[3] as f64 / 255.0)
+    )
+}
+
+/// Formats a number with at most 3 decimals and without trailing zeros.
+fn number(value: f64) -> String {
+    let formatted = format!("{:.3}", value);
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    match formatted {
+        "-0" => "0".to_string(),
+        _ => formatted.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_formats_numbers_compactly() {
+        assert_eq!("12", number(12.0));
+        assert_eq!("0.5", number(0.5));
+        assert_eq!("3.142", number(std::f64::consts::PI));
+        assert_eq!("0", number(-0.0001));
+    }
+
+    #[test]
+    fn it_writes_a_rotated_ellipse() {
+        let ellipse = Ellipse::new()
+            .u(10.0)
+            .v(20.0)
+            .a(5.0)
+            .b(2.5)
+            .angle(std::f64::consts::FRAC_PI_2)
+            .build();
+
+        assert_eq!(
+            r#"<ellipse cx="10" cy="20" rx="5" ry="2.5" transform="rotate(90 10 20)" fill="rgb(1,2,3)" fill-opacity="1"/>"#,
+            ellipse.to_svg_element(Rgba([1, 2, 3, 255]))
+        );
+    }
+
+    #[test]
+    fn it_writes_rectangles() {
+        let rect = Rectangle::new().origin(1.0, 2.0).aspect(3.0, 4.0).build();
+        assert_eq!(
+            r#"<rect x="1" y="2" width="3" height="4" fill="rgb(0,0,0)" fill-opacity="0"/>"#,
+            rect.to_svg_element(Rgba([0, 0, 0, 0]))
+        );
+
+        let rect = Rectangle::new()
+            .origin(1.0, 2.0)
+            .aspect(3.0, 4.0)
+            .angle(std::f64::consts::PI)
+            .build();
+        assert_eq!(
+            r#"<rect x="0" y="0" width="3" height="4" transform="translate(1 2) rotate(180)" fill="rgb(0,0,0)" fill-opacity="0"/>"#,
+            rect.to_svg_element(Rgba([0, 0, 0, 0]))
+        );
+    }
+
+    #[test]
+    fn it_writes_polygons() {
+        let triangle = Triangle::new(Point::zero(), Point::new(1.0, 0.0), Point::new(0.0, 1.0));
+
+        assert_eq!(
+            r#"<polygon points="0,0 1,0 0,1" fill="rgb(255,0,0)" fill-opacity="0.502"/>"#,
+            AnyShape::from(triangle).to_svg_element(Rgba([255, 0, 0, 128]))
+        );
+    }
+
+    #[test]
+    fn it_writes_a_document_in_order() {
+        let shapes: Vec<(AnyShape, Rgba<u8>)> = vec![
+            (Rectangle::default().into(), Rgba([1, 1, 1, 255])),
+            (
+                Ellipse::new().a(1.0).b(1.0).build().into(),
+                Rgba([2, 2, 2, 255]),
+            ),
+        ];
+
+        let svg = to_svg(
+            8,
+            4,
+            Rgba([9, 9, 9, 255]),
+            shapes.iter().map(|(s, c)| (s, *c)),
+        );
+        let lines: Vec<&str> = svg.lines().collect();
+
+        assert_eq!(5, lines.len());
+        assert!(lines[0].starts_with("<svg") && lines[0].contains(r#"viewBox="0 0 8 4""#));
+        assert!(lines[1].contains(r#"width="8" height="4" fill="rgb(9,9,9)""#));
+        assert!(lines[2].starts_with("<rect"));
+        assert!(lines[3].starts_with("<ellipse"));
+        assert_eq!("</svg>", lines[4]);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::math::{shapes::Shape, Coverage, Rasterizer};
+use image::{Rgba, RgbaImage};
+
+/// A canvas is an RGBA image on which shapes can be composited with a color,
+/// by using the source-over alpha blending. It can then be exported as a
+/// RGBA image encoded on 8-bits.
+///
+/// # Example
+///
+/// ```
+/// use image::Rgba;
+/// use libgeometrize::images::Canvas;
+/// use libgeometrize::math::{shapes::Ellipse, Rasterizer};
+///
+/// // Drawing a translucent red circle on a white canvas
+/// let mut canvas = Canvas::new(64, 64, Rgba([255, 255, 255, 255]));
+/// let circle = Ellipse::new().u(32.0).v(32.0).a(16.0).b(16.0).build();
+/// canvas.draw(&circle, Rgba([255, 0, 0, 128]), Rasterizer::Antialiased);
+///
+/// let image = canvas.to_rgba8();
+/// assert_eq!(&Rgba([255, 127, 127, 255]), image.get_pixel(32, 32));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Canvas {
+    inner: RgbaImage,
+}
+
+impl Canvas {
+    /// Instanciates a new canvas with the given dimensions filled with a
+    /// background color.
+    pub fn new(width: u32, height: u32, background: Rgba<u8>) -> Self {
+        Self {
+            inner: RgbaImage::from_pixel(width, height, background),
+        }
+    }
+
+    /// Returns the dimensions of the current canvas.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.inner.dimensions()
+    }
+
+    /// Returns the width of the current canvas.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    /// Returns the height of the current canvas.
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    /// Retrieves a reference to a pixel of the canvas thanks to user-supplied
+    /// coordinates.
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<&Rgba<u8>> {
+        if x < self.width() && y < self.height() {
+            Some(self.inner.get_pixel(x, y))
+        } else {
+            None
+        }
+    }
+
+    /// Rasterizes a shape with the given rasterization method and composites
+    /// it on the canvas with a color.
+    pub fn draw<S: Shape>(&mut self, shape: &S, color: Rgba<u8>, rasterizer: Rasterizer) {
+        let (width, height) = self.dimensions();
+
+        match rasterizer {
+            Rasterizer::Aliased => self.draw_lines(&shape.rasterize(width, height), color),
+            Rasterizer::Antialiased => {
+                self.draw_lines(&shape.rasterize_antialiased(width, height), color)
+            }
+        }
+    }
+
+    /// Composites a color on the pixels covered by the given scanlines. The
+    /// opacity of the color is weighted by the coverage of each pixel.
+    pub fn draw_lines<L: Coverage>(&mut self, lines: &[L], color: Rgba<u8>) {
+        for line in lines {
+            for x in line.x1()..=line.x2() {
+                let px = self.inner.get_pixel_mut(x, line.y());
+                *px = blend(*px, color, line.coverage(x));
+            }
+        }
+    }
+
+    /// Copies the pixels covered by the given scanlines from another canvas of
+    /// the same dimensions, whatever their coverage is.
+    pub fn copy_lines<L: Coverage>(&mut self, source: &Canvas, lines: &[L]) {
+        for line in lines {
+            for x in line.x1()..=line.x2() {
+                self.inner
+                    .put_pixel(x, line.y(), *source.inner.get_pixel(x, line.y()));
+            }
+        }
+    }
+
+    /// Returns a reference to the RGBA image backing the current canvas.
+    #[inline]
+    pub fn as_rgba8(&self) -> &RgbaImage {
+        &self.inner
+    }
+
+    /// Instanciates a copy of the current canvas as a RGBA image encoded on
+    /// 8-bits.
+    pub fn to_rgba8(&self) -> RgbaImage {
+        self.inner.clone()
+    }
+}
+
+impl From<RgbaImage> for Canvas {
+    fn from(inner: RgbaImage) -> Self {
+        Self { inner }
+    }
+}
+
+impl From<Canvas> for RgbaImage {
+    fn from(canvas: Canvas) -> Self {
+        canvas.inner
+    }
+}
+
+/// Composites a source color over a destination color by using the
+/// source-over operator, the opacity of the source being weighted by
+/// `coverage`.
+fn blend(dst: Rgba<u8>, src: Rgba<u8>, coverage: f64) -> Rgba<u8> {
+    let src_alpha = src[3] as f64 / 255.0 * coverage;
+    let dst_alpha = dst[3] as f64 / 255.0;
+    let alpha = src_alpha + dst_alpha * (1.0 - src_alpha);
+
+    if alpha <= 0.0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+
+    let channel = |idx: usize| {
+        let value =
+            (src[idx] as f64 * src_alpha + dst[idx] as f64 * dst_alpha * (1.0 - src_alpha)) / alpha;
+        value.round().clamp(0.0, 255.0) as u8
+    };
+
+    Rgba([
+        channel(0),
+        channel(1),
+        channel(2),
+        (alpha * 255.0).round() as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::shapes::Rectangle;
+
+    #[test]
+    fn it_blends_colors_with_the_source_over_operator() {
+        let white = Rgba([255, 255, 255, 255]);
+
+        assert_eq!(
+            Rgba([0, 0, 0, 255]),
+            blend(white, Rgba([0, 0, 0, 255]), 1.0)
+        );
+        assert_eq!(
+            Rgba([128, 128, 255, 255]),
+            blend(white, Rgba([0, 0, 255, 255]), 0.5)
+        );
+        assert_eq!(white, blend(white, Rgba([0, 0, 0, 0]), 1.0));
+        assert_eq!(
+            Rgba([10, 20, 30, 51]),
+            blend(Rgba([0, 0, 0, 0]), Rgba([10, 20, 30, 51]), 1.0)
+        );
+    }
+
+    #[test]
+    fn it_composites_a_shape_on_the_canvas() {
+        let mut canvas = Canvas::new(8, 8, Rgba([255, 255, 255, 255]));
+        let rect = Rectangle::new().origin(2.0, 2.0).aspect(4.0, 4.0).build();
+        canvas.draw(&rect, Rgba([0, 0, 0, 255]), Rasterizer::Aliased);
+
+        let image = canvas.to_rgba8();
+        for (x, y, px) in image.enumerate_pixels() {
+            if (2..6).contains(&x) && (2..6).contains(&y) {
+                assert_eq!(&Rgba([0, 0, 0, 255]), px);
+            } else {
+                assert_eq!(&Rgba([255, 255, 255, 255]), px);
+            }
+        }
+    }
+
+    #[test]
+    fn it_composites_the_partially_covered_pixels_of_a_shape() {
+        let mut canvas = Canvas::new(8, 8, Rgba([255, 255, 255, 255]));
+        let rect = Rectangle::new().origin(2.5, 2.0).aspect(4.0, 4.0).build();
+        canvas.draw(&rect, Rgba([0, 0, 0, 255]), Rasterizer::Antialiased);
+
+        assert_eq!(Some(&Rgba([128, 128, 128, 255])), canvas.get_pixel(2, 3));
+        assert_eq!(Some(&Rgba([0, 0, 0, 255])), canvas.get_pixel(3, 3));
+        assert_eq!(Some(&Rgba([128, 128, 128, 255])), canvas.get_pixel(6, 3));
+        assert_eq!(Some(&Rgba([255, 255, 255, 255])), canvas.get_pixel(7, 3));
+    }
+}
@@ -0,0 +1,176 @@
+use super::Canvas;
+use crate::math::Coverage;
+use image::{Rgba, RgbaImage};
+
+/// Computes the color which, once composited with the opacity `alpha` on the
+/// pixels of the current canvas covered by the given scanlines, minimizes the
+/// squared error between these pixels and the target image.
+///
+/// Every pixel `c` of the canvas becomes `c + a (s - c)` once the color `s` is
+/// composited with an opacity `a`, being `alpha` weighted by the coverage of the
+/// pixel. The least squares solution for the target pixels `t` is then:
+/// $s = \frac{\sum a (t - (1 - a) c)}{\sum a^2}$
+///
+/// If the scanlines don't cover any pixel, a black color is returned. The
+/// canvas is assumed to be opaque and the target image must have the same
+/// dimensions than the canvas.
+///
+/// # Example
+///
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use libgeometrize::images::{optimal_color, Canvas};
+/// use libgeometrize::math::shapes::{Rectangle, Shape};
+///
+/// let target = RgbaImage::from_pixel(16, 16, Rgba([200, 100, 50, 255]));
+/// let canvas = Canvas::new(16, 16, Rgba([0, 0, 0, 255]));
+/// let rect = Rectangle::new().origin(4.0, 4.0).aspect(8.0, 8.0).build();
+///
+/// let color = optimal_color(&target, &canvas, &rect.rasterize(16, 16), 255);
+/// assert_eq!(Rgba([200, 100, 50, 255]), color);
+/// ```
+pub fn optimal_color<L: Coverage>(
+    target: &RgbaImage,
+    current: &Canvas,
+    lines: &[L],
+    alpha: u8,
+) -> Rgba<u8> {
+    let current = current.as_rgba8();
+    let opacity = alpha as f64 / 255.0;
+
+    let mut sums = [0.0; 3];
+    let mut weight = 0.0;
+    for line in lines {
+        for x in line.x1()..=line.x2() {
+            let a = opacity * line.coverage(x);
+            let t = target.get_pixel(x, line.y());
+            let c = current.get_pixel(x, line.y());
+
+            for (idx, sum) in sums.iter_mut().enumerate() {
+                *sum += a * (t[idx] as f64 - (1.0 - a) * c[idx] as f64);
+            }
+            weight += a * a;
+        }
+    }
+
+    if weight <= 0.0 {
+        return Rgba([0, 0, 0, alpha]);
+    }
+
+    let channel = |sum: f64| (sum / weight).round().clamp(0.0, 255.0) as u8;
+    Rgba([channel(sums[0]), channel(sums[1]), channel(sums[2]), alpha])
+}
+
+/// Computes the average color of an image, which is a good background color to
+/// start approximating it with shapes. An empty image has a black average color.
+pub fn average_color(image: &RgbaImage) -> Rgba<u8> {
+    let count = image.width() as f64 * image.height() as f64;
+    if count == 0.0 {
+        return Rgba([0, 0, 0, 255]);
+    }
+
+    let mut sums = [0.0; 4];
+    for px in image.pixels() {
+        for (idx, sum) in sums.iter_mut().enumerate() {
+            *sum += px[idx] as f64;
+        }
+    }
+
+    let channel = |sum: f64| (sum / count).round() as u8;
+    Rgba([
+        channel(sums[0]),
+        channel(sums[1]),
+        channel(sums[2]),
+        channel(sums[3]),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{shapes::Rectangle, shapes::Shape, Rasterizer, Scanline};
+
+    #[test]
+    fn it_computes_the_mean_color_for_an_opaque_shape() {
+        let target = RgbaImage::from_fn(4, 1, |x, _| Rgba([x as u8 * 10, 0, 255, 255]));
+        let canvas = Canvas::new(4, 1, Rgba([255, 255, 255, 255]));
+        let lines = vec![Scanline::new(0, 0, 3)];
+
+        assert_eq!(
+            Rgba([15, 0, 255, 255]),
+            optimal_color(&target, &canvas, &lines, 255)
+        );
+    }
+
+    #[test]
+    fn it_computes_a_color_reaching_the_target_once_blended() {
+        let target = RgbaImage::from_pixel(16, 16, Rgba([100, 120, 140, 255]));
+        let mut canvas = Canvas::new(16, 16, Rgba([50, 50, 50, 255]));
+        let rect = Rectangle::new().origin(2.3, 2.6).aspect(9.0, 7.0).build();
+
+        let color = optimal_color(&target, &canvas, &rect.rasterize(16, 16), 128);
+        canvas.draw(&rect, color, Rasterizer::Aliased);
+
+        assert_eq!(128, color[3]);
+        let px = canvas.get_pixel(5, 5).unwrap();
+        for idx in 0..3 {
+            assert!((px[idx] as i32 - target.get_pixel(5, 5)[idx] as i32).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn it_minimizes_the_error_of_partially_covered_pixels() {
+        let target = RgbaImage::from_fn(16, 16, |x, y| Rgba([x as u8 * 16, y as u8 * 16, 90, 255]));
+        let canvas = Canvas::new(16, 16, Rgba([50, 50, 50, 255]));
+        let rect = Rectangle::new()
+            .origin(7.3, 1.6)
+            .aspect(9.0, 7.0)
+            .angle(0.5)
+            .build();
+        let lines = rect.rasterize_antialiased(16, 16);
+
+        let error = |color: Rgba<u8>| {
+            let mut canvas = canvas.clone();
+            canvas.draw_lines(&lines, color);
+            canvas
+                .as_rgba8()
+                .pixels()
+                .zip(target.pixels())
+                .map(|(a, b)| {
+                    (0..3)
+                        .map(|idx| (a[idx] as f64 - b[idx] as f64).powi(2))
+                        .sum::<f64>()
+                })
+                .sum::<f64>()
+        };
+
+        let best = optimal_color(&target, &canvas, &lines, 128);
+        for idx in 0..3 {
+            for delta in [-4, 4] {
+                let mut other = best;
+                other[idx] = (other[idx] as i32 + delta).clamp(0, 255) as u8;
+
+                assert!(error(best) <= error(other));
+            }
+        }
+    }
+
+    #[test]
+    fn it_computes_the_average_color_of_an_image() {
+        let image = RgbaImage::from_fn(2, 2, |x, y| Rgba([x as u8 * 100, y as u8 * 50, 10, 255]));
+
+        assert_eq!(Rgba([50, 25, 10, 255]), average_color(&image));
+    }
+
+    #[test]
+    fn it_returns_a_black_color_without_covered_pixels() {
+        let target = RgbaImage::new(4, 4);
+        let canvas = Canvas::new(4, 4, Rgba([255, 255, 255, 255]));
+        let lines: Vec<Scanline> = Vec::new();
+
+        assert_eq!(
+            Rgba([0, 0, 0, 64]),
+            optimal_color(&target, &canvas, &lines, 64)
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use image::{Rgb, RgbImage};
+
+/// The viridis colormap from matplotlib, sampled at ten evenly spaced stops.
+const VIRIDIS: [u32; 10] = [
+    0x440154, 0x482878, 0x3e4a89, 0x31688e, 0x26828e, 0x1f9e89, 0x35b779, 0x6dcd59, 0xb4de2c,
+    0xfde725,
+];
+
+/// The magma colormap from matplotlib, sampled at ten evenly spaced stops.
+const MAGMA: [u32; 10] = [
+    0x000004, 0x180f3e, 0x451077, 0x721f81, 0x9f2f7f, 0xcd4071, 0xf1605d, 0xfd9567, 0xfec98d,
+    0xfcfdbf,
+];
+
+/// The inferno colormap from matplotlib, sampled at ten evenly spaced stops.
+const INFERNO: [u32; 10] = [
+    0x000004, 0x1b0c42, 0x4b0c6b, 0x781c6d, 0xa52c60, 0xcf4446, 0xed6925, 0xfb9a06, 0xf7d03c,
+    0xfcffa4,
+];
+
+/// The coefficients of the polynomial approximation of the turbo colormap,
+/// from the lowest to the highest degree, for each of the three channels.
+const TURBO: [[f64; 6]; 3] = [
+    [
+        0.13572138,
+        4.61539260,
+        -42.66032258,
+        132.13108234,
+        -152.94239396,
+        59.28637943,
+    ],
+    [
+        0.09140261,
+        2.19418839,
+        4.84296658,
+        -14.18503333,
+        4.27729857,
+        2.82956604,
+    ],
+    [
+        0.10667330,
+        12.64194608,
+        -60.58204836,
+        110.36276771,
+        -89.90310912,
+        27.34824973,
+    ],
+];
+
+/// Defines a colormap, which turns a normalized heat between `0.0` and `1.0`
+/// into a color to render heatmaps in a readable way.
+///
+/// # Example
+///
+/// ```
+/// use image::Rgb;
+/// use libgeometrize::images::Colormap;
+///
+/// assert_eq!(Rgb([0x44, 0x01, 0x54]), Colormap::Viridis.color(0.0));
+///
+/// // A custom gradient going from blue to red through white
+/// let colormap = Colormap::gradient(vec![
+///     (0.0, Rgb([0, 0, 255])),
+///     (1.0, Rgb([255, 0, 0])),
+///     (0.5, Rgb([255, 255, 255])),
+/// ]);
+/// assert_eq!(Rgb([255, 255, 255]), colormap.color(0.5));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Colormap {
+    /// The perceptually uniform viridis colormap, going from purple to yellow.
+    Viridis,
+
+    /// The perceptually uniform magma colormap, going from black to pale pink.
+    Magma,
+
+    /// The perceptually uniform inferno colormap, going from black to pale
+    /// yellow.
+    Inferno,
+
+    /// The turbo colormap, a smooth rainbow going from dark blue to dark red.
+    Turbo,
+
+    /// A gradient linearly interpolating colors between stops, which is built
+    /// by [`Colormap::gradient`].
+    Gradient(Gradient),
+}
+
+/// The stops of a gradient colormap, given by their position between `0.0`
+/// and `1.0` and sorted by increasing position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient(Vec<(f64, Rgb<u8>)>);
+
+impl Gradient {
+    /// Returns the stops of the current gradient, sorted by increasing
+    /// position.
+    #[inline]
+    pub fn stops(&self) -> &[(f64, Rgb<u8>)] {
+        &self.0
+    }
+}
+
+impl Colormap {
+    /// Instanciates a new gradient colormap from stops given in any order.
+    /// Stops whose position isn't finite are ignored, and a gradient without
+    /// any stop is completely black.
+    pub fn gradient<I>(stops: I) -> Self
+    where
+        I: IntoIterator<Item = (f64, Rgb<u8>)>,
+    {
+        let mut stops: Vec<_> = stops
+            .into_iter()
+            .filter(|(position, _)| position.is_finite())
+            .collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Colormap::Gradient(Gradient(stops))
+    }
+
+    /// Returns the color of a normalized heat, which is clamped between `0.0`
+    /// and `1.0`.
+    pub fn color(&self, heat: f64) -> Rgb<u8> {
+        let heat = if heat.is_nan() {
+            0.0
+        } else {
+            heat.clamp(0.0, 1.0)
+        };
+
+        match self {
+            Colormap::Viridis => sample_table(&VIRIDIS, heat),
+            Colormap::Magma => sample_table(&MAGMA, heat),
+            Colormap::Inferno => sample_table(&INFERNO, heat),
+            Colormap::Turbo => {
+                let channel = |coefficients: &[f64; 6]| {
+                    let value = coefficients.iter().rev().fold(0.0, |acc, c| acc * heat + c);
+                    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+                };
+
+                Rgb([channel(&TURBO[0]), channel(&TURBO[1]), channel(&TURBO[2])])
+            }
+            Colormap::Gradient(gradient) => sample_gradient(gradient.stops(), heat),
+        }
+    }
+
+    /// Renders a vertical colorbar of the given dimensions, the hottest colors
+    /// being on top.
+    pub fn colorbar(&self, width: u32, height: u32) -> RgbImage {
+        let colors: Vec<_> = (0..height)
+            .map(|y| {
+                let heat = if height > 1 {
+                    1.0 - y as f64 / (height - 1) as f64
+                } else {
+                    1.0
+                };
+                self.color(heat)
+            })
+            .collect();
+
+        RgbImage::from_fn(width, height, |_, y| colors[y as usize])
+    }
+}
+
+/// Samples a table of evenly spaced colors encoded as hexadecimal words, at a
+/// heat between `0.0` and `1.0`.
+fn sample_table(table: &[u32], heat: f64) -> Rgb<u8> {
+    let hex = |idx: usize| {
+        let hex = table[idx];
+        Rgb([(hex >> 16) as u8, (hex >> 8) as u8, hex as u8])
+    };
+
+    let scaled = heat * (table.len() - 1) as f64;
+    let idx = (scaled.floor() as usize).min(table.len() - 2);
+
+    lerp(hex(idx), hex(idx + 1), scaled - idx as f64)
+}
+
+/// Linearly interpolates the color of a heat between sorted gradient stops.
+/// Heats lying outside of the stops take the color of the closest one.
+fn sample_gradient(stops: &[(f64, Rgb<u8>)], heat: f64) -> Rgb<u8> {
+    let next = stops.iter().position(|(position, _)| *position >= heat);
+
+    match next {
+        None => stops.last().map_or(Rgb([0, 0, 0]), |(_, color)| *color),
+        Some(0) => stops[0].1,
+        Some(idx) => {
+            let (start, from) = stops[idx - 1];
+            let (end, to) = stops[idx];
+
+            lerp(from, to, (heat - start) / (end - start))
+        }
+    }
+}
+
+/// Linearly interpolates between two colors, `t` going from `0.0` to `1.0`.
+fn lerp(from: Rgb<u8>, to: Rgb<u8>, t: f64) -> Rgb<u8> {
+    let mut color = from;
+    for (channel, target) in color.0.iter_mut().zip(to.0.iter()) {
+        *channel = (*channel as f64 + (*target as f64 - *channel as f64) * t).round() as u8;
+    }
+
+    color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_samples_the_ends_of_the_builtin_colormaps() {
+        assert_eq!(Rgb([0xfd, 0xe7, 0x25]), Colormap::Viridis.color(1.0));
+        assert_eq!(Rgb([0x00, 0x00, 0x04]), Colormap::Magma.color(0.0));
+        assert_eq!(Rgb([0xfc, 0xff, 0xa4]), Colormap::Inferno.color(2.0));
+        assert_eq!(Rgb([0x44, 0x01, 0x54]), Colormap::Viridis.color(f64::NAN));
+    }
+
+    #[test]
+    fn it_samples_the_inner_stops_of_the_builtin_colormaps() {
+        assert_eq!(Rgb([0x48, 0x28, 0x78]), Colormap::Viridis.color(1.0 / 9.0));
+        assert_eq!(Rgb([0x1f, 0x9e, 0x89]), Colormap::Viridis.color(5.0 / 9.0));
+        assert_eq!(Rgb([0x46, 0x15, 0x66]), Colormap::Viridis.color(0.5 / 9.0));
+    }
+
+    #[test]
+    fn it_approximates_the_turbo_colormap() {
+        // Turbo goes from blue to dark red through green
+        let Rgb([r, g, b]) = Colormap::Turbo.color(0.1);
+        assert!(b > r && b > g);
+
+        let Rgb([r, g, b]) = Colormap::Turbo.color(0.5);
+        assert!(g > r && g > b);
+
+        let Rgb([r, g, b]) = Colormap::Turbo.color(1.0);
+        assert!(r > g && r > b);
+    }
+
+    #[test]
+    fn it_interpolates_between_gradient_stops() {
+        let colormap =
+            Colormap::gradient(vec![(0.75, Rgb([200, 100, 0])), (0.25, Rgb([0, 100, 200]))]);
+
+        assert_eq!(Rgb([0, 100, 200]), colormap.color(0.0));
+        assert_eq!(Rgb([100, 100, 100]), colormap.color(0.5));
+        assert_eq!(Rgb([200, 100, 0]), colormap.color(1.0));
+        assert_eq!(Rgb([0, 0, 0]), Colormap::gradient(Vec::new()).color(0.5));
+    }
+
+    #[test]
+    fn it_ignores_gradient_stops_at_non_finite_positions() {
+        let colormap = Colormap::gradient(vec![
+            (f64::NAN, Rgb([255, 255, 255])),
+            (1.0, Rgb([200, 100, 0])),
+            (f64::INFINITY, Rgb([255, 255, 255])),
+            (0.0, Rgb([0, 100, 200])),
+        ]);
+
+        match &colormap {
+            Colormap::Gradient(gradient) => assert_eq!(2, gradient.stops().len()),
+            colormap => panic!("unexpected colormap {:?}", colormap),
+        }
+        assert_eq!(Rgb([100, 100, 100]), colormap.color(0.5));
+    }
+
+    #[test]
+    fn it_renders_a_colorbar() {
+        let colorbar = Colormap::Magma.colorbar(4, 10);
+
+        assert_eq!((4, 10), colorbar.dimensions());
+        assert_eq!(&Rgb([0xfc, 0xfd, 0xbf]), colorbar.get_pixel(3, 0));
+        assert_eq!(&Rgb([0x00, 0x00, 0x04]), colorbar.get_pixel(0, 9));
+    }
+}
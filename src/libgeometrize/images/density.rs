@@ -0,0 +1,130 @@
+use super::Heatmap;
+use crate::math::shapes::Shape;
+
+impl Heatmap {
+    /// Instanciates a new heatmap counting, for each pixel, the number of
+    /// shapes covering it. A pixel is covered by a shape if its center is
+    /// inside it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use libgeometrize::images::Heatmap;
+    /// use libgeometrize::math::shapes::ShapeKind;
+    /// use libgeometrize::optimizer::{HillClimbing, Model};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let target = RgbaImage::from_fn(32, 32, |x, y| Rgba([x as u8 * 8, y as u8 * 8, 0, 255]));
+    /// let mut model = Model::new(target);
+    ///
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// for _ in 0..10 {
+    ///     model.step(&mut rng, &[ShapeKind::RotatedRectangle], &HillClimbing::default());
+    /// }
+    ///
+    /// // Showing where the shapes landed during the run
+    /// let density = Heatmap::from_shapes(32, 32, model.shapes().iter().map(|c| &c.shape));
+    /// let preview = density.to_luma8(1.0);
+    /// ```
+    pub fn from_shapes<'a, S, I>(width: u32, height: u32, shapes: I) -> Self
+    where
+        S: Shape + 'a,
+        I: IntoIterator<Item = &'a S>,
+    {
+        let mut heatmap = Heatmap::new(width, height);
+        for shape in shapes {
+            heatmap.stamp(shape);
+        }
+
+        heatmap
+    }
+
+    /// Increments the heat of all the pixels covered by a shape, the parts of
+    /// the shape lying outside of the heatmap being ignored.
+    pub fn stamp<S: Shape + ?Sized>(&mut self, shape: &S) {
+        for line in shape.rasterize(self.width(), self.height()) {
+            for x in line.x1..=line.x2 {
+                if let Some(heat) = self.get_pixel_mut(x, line.y) {
+                    *heat += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::shapes::{AnyShape, Ellipse, Polygon, Rectangle, Triangle};
+    use crate::math::Point;
+
+    fn total_heat(heatmap: &Heatmap) -> u64 {
+        heatmap.as_raw().iter().sum()
+    }
+
+    #[test]
+    fn it_stamps_overlapping_shapes() {
+        let a = Rectangle::new().origin(0.0, 0.0).aspect(3.0, 2.0).build();
+        let b = Rectangle::new().origin(1.0, 1.0).aspect(3.0, 2.0).build();
+
+        let heatmap = Heatmap::from_shapes(4, 3, &[a, b]);
+
+        assert_eq!(vec![1u64, 1, 1, 0, 1, 2, 2, 1, 0, 1, 1, 1], heatmap);
+    }
+
+    #[test]
+    fn it_stamps_every_kind_of_shape() {
+        let shapes: Vec<AnyShape> = vec![
+            Ellipse::new()
+                .u(20.0)
+                .v(20.0)
+                .a(10.0)
+                .b(5.0)
+                .angle(0.5)
+                .build()
+                .into(),
+            Rectangle::new()
+                .origin(50.0, 10.0)
+                .aspect(20.0, 10.0)
+                .angle(std::f64::consts::FRAC_PI_6)
+                .build()
+                .into(),
+            Triangle::new(
+                Point::new(10.0, 60.0),
+                Point::new(40.0, 60.0),
+                Point::new(10.0, 90.0),
+            )
+            .into(),
+            Polygon::new(vec![
+                Point::new(60.0, 60.0),
+                Point::new(90.0, 60.0),
+                Point::new(90.0, 90.0),
+                Point::new(60.0, 90.0),
+            ])
+            .into(),
+        ];
+
+        for (shape, area) in shapes.iter().zip(&[157.08, 200.0, 450.0, 900.0]) {
+            let heatmap = Heatmap::from_shapes(100, 100, Some(shape));
+            let heat = total_heat(&heatmap) as f64;
+
+            assert!((heat - area).abs() / area < 0.05, "{} != {}", heat, area);
+        }
+    }
+
+    #[test]
+    fn it_accumulates_the_density_of_several_runs() {
+        let first = [Ellipse::new().u(8.0).v(8.0).a(4.0).b(4.0).build()];
+        let second = [Ellipse::new().u(8.0).v(8.0).a(6.0).b(3.0).build()];
+
+        let mut density = Heatmap::from_shapes(16, 16, &first);
+        density += Heatmap::from_shapes(16, 16, &second);
+
+        assert_eq!(
+            Heatmap::from_shapes(16, 16, first.iter().chain(&second)),
+            density
+        );
+        assert_eq!(Some(&2), density.get_pixel(8, 8));
+    }
+}
@@ -0,0 +1,231 @@
+use super::{Canvas, Heatmap};
+use crate::math::Coverage;
+use image::{Rgba, RgbaImage};
+
+/// Computes the root-mean-square difference between a target image and the
+/// current canvas, over the four channels of all their pixels. The difference
+/// is normalized between `0.0` for identical images and `1.0`. The difference
+/// between two empty images is `0.0`.
+///
+/// # Panics
+///
+/// Panics if the target image and the canvas have different dimensions.
+///
+/// # Example
+///
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use libgeometrize::images::{difference_full, Canvas};
+///
+/// let target = RgbaImage::from_pixel(16, 16, Rgba([255, 255, 255, 255]));
+/// let canvas = Canvas::new(16, 16, Rgba([255, 255, 255, 255]));
+///
+/// assert_eq!(0.0, difference_full(&target, &canvas));
+/// ```
+pub fn difference_full(target: &RgbaImage, current: &Canvas) -> f64 {
+    assert_eq!(
+        target.dimensions(),
+        current.dimensions(),
+        "images must have the same dimensions"
+    );
+
+    let total: f64 = target
+        .pixels()
+        .zip(current.as_rgba8().pixels())
+        .map(|(t, c)| squared_difference(t, c))
+        .sum();
+
+    normalize(total, target.width(), target.height())
+}
+
+/// Computes the root-mean-square difference between a target image and the
+/// canvas `after`, knowing the difference `score` between the target image and
+/// the canvas `before` and that both canvases only differ on the pixels of the
+/// given scanlines.
+///
+/// Only the pixels of the scanlines are visited, which makes this function far
+/// cheaper than [`difference_full`] to score a single shape on a large image.
+pub fn difference_partial<L: Coverage>(
+    target: &RgbaImage,
+    before: &Canvas,
+    after: &Canvas,
+    score: f64,
+    lines: &[L],
+) -> f64 {
+    let (width, height) = (target.width(), target.height());
+    let (before, after) = (before.as_rgba8(), after.as_rgba8());
+
+    let mut total = (score * 255.0).powi(2) * (width as f64 * height as f64 * 4.0);
+    for line in lines {
+        for x in line.x1()..=line.x2() {
+            let t = target.get_pixel(x, line.y());
+            total -= squared_difference(t, before.get_pixel(x, line.y()));
+            total += squared_difference(t, after.get_pixel(x, line.y()));
+        }
+    }
+
+    normalize(total.max(0.0), width, height)
+}
+
+/// Defines how the error between two pixels is measured when building a
+/// heatmap of the differences between two images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorMetric {
+    /// The sum of the absolute differences of the four channels.
+    Absolute,
+
+    /// The sum of the squared differences of the four channels, which
+    /// emphasizes the largest errors.
+    Squared,
+}
+
+impl Heatmap {
+    /// Instanciates a new heatmap of the per-pixel error between a target
+    /// image and its approximation, measured with the given metric.
+    ///
+    /// The approximation must have the same dimensions than the target image.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use libgeometrize::images::{Canvas, ErrorMetric, Heatmap, HeatmapSampler};
+    ///
+    /// let target = RgbaImage::from_fn(16, 16, |x, _| Rgba([x as u8 * 16, 0, 0, 255]));
+    /// let canvas = Canvas::new(16, 16, Rgba([0, 0, 0, 255]));
+    ///
+    /// let heatmap = Heatmap::from_difference(&target, canvas.as_rgba8(), ErrorMetric::Absolute);
+    /// assert_eq!(Some(&32), heatmap.get_pixel(2, 0));
+    ///
+    /// // The poorly approximated regions can then drive the placement of shapes
+    /// let sampler = HeatmapSampler::new(&heatmap);
+    /// ```
+    pub fn from_difference(target: &RgbaImage, current: &RgbaImage, metric: ErrorMetric) -> Self {
+        assert_eq!(
+            target.dimensions(),
+            current.dimensions(),
+            "images must have the same dimensions"
+        );
+
+        Heatmap::from_fn(target.width(), target.height(), |x, y| {
+            let (t, c) = (target.get_pixel(x, y), current.get_pixel(x, y));
+
+            match metric {
+                ErrorMetric::Absolute => absolute_difference(t, c),
+                ErrorMetric::Squared => squared_difference(t, c) as u64,
+            }
+        })
+    }
+}
+
+/// Returns the sum of the absolute differences of the channels of two pixels.
+#[inline]
+fn absolute_difference(a: &Rgba<u8>, b: &Rgba<u8>) -> u64 {
+    a.0.iter()
+        .zip(b.0.iter())
+        .map(|(a, b)| (*a as i64 - *b as i64).unsigned_abs())
+        .sum()
+}
+
+/// Returns the sum of the squared differences of the channels of two pixels.
+#[inline]
+fn squared_difference(a: &Rgba<u8>, b: &Rgba<u8>) -> f64 {
+    a.0.iter()
+        .zip(b.0.iter())
+        .map(|(a, b)| {
+            let diff = *a as f64 - *b as f64;
+            diff * diff
+        })
+        .sum()
+}
+
+/// Turns a sum of squared differences over an image into a normalized
+/// root-mean-square difference.
+#[inline]
+fn normalize(total: f64, width: u32, height: u32) -> f64 {
+    if width == 0 || height == 0 {
+        return 0.0;
+    }
+
+    (total / (width as f64 * height as f64 * 4.0)).sqrt() / 255.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::shapes::{Ellipse, Shape};
+
+    #[test]
+    fn it_computes_the_difference_between_opposite_images() {
+        let target = RgbaImage::from_pixel(8, 8, Rgba([255, 255, 255, 255]));
+        let canvas = Canvas::new(8, 8, Rgba([0, 0, 0, 255]));
+
+        // Three channels out of four are completely different
+        assert!((difference_full(&target, &canvas) - 0.75_f64.sqrt()).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn it_computes_no_difference_between_empty_images() {
+        let target = RgbaImage::new(0, 0);
+        let canvas = Canvas::new(0, 0, Rgba([0, 0, 0, 255]));
+
+        assert_eq!(0.0, difference_full(&target, &canvas));
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_requires_images_of_the_same_dimensions() {
+        let target = RgbaImage::new(8, 8);
+        let canvas = Canvas::new(8, 4, Rgba([0, 0, 0, 255]));
+
+        difference_full(&target, &canvas);
+    }
+
+    #[test]
+    fn it_agrees_with_the_full_difference() {
+        let target = RgbaImage::from_fn(64, 48, |x, y| Rgba([x as u8 * 4, y as u8 * 5, 128, 255]));
+        let before = Canvas::new(64, 48, Rgba([20, 40, 60, 255]));
+        let score = difference_full(&target, &before);
+
+        let ellipse = Ellipse::new()
+            .u(40.0)
+            .v(20.0)
+            .a(18.0)
+            .b(9.0)
+            .angle(0.7)
+            .build();
+
+        let lines = ellipse.rasterize(64, 48);
+        let mut after = before.clone();
+        after.draw_lines(&lines, Rgba([200, 10, 90, 160]));
+        let partial = difference_partial(&target, &before, &after, score, &lines);
+        assert!((partial - difference_full(&target, &after)).abs() < 1e-9);
+
+        let lines = ellipse.rasterize_antialiased(64, 48);
+        let mut after = before.clone();
+        after.draw_lines(&lines, Rgba([200, 10, 90, 160]));
+        let partial = difference_partial(&target, &before, &after, score, &lines);
+        assert!((partial - difference_full(&target, &after)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_builds_a_heatmap_of_the_errors() {
+        let target = RgbaImage::from_fn(4, 2, |x, y| Rgba([x as u8 * 10, y as u8 * 20, 5, 255]));
+        let current = RgbaImage::from_pixel(4, 2, Rgba([10, 10, 0, 255]));
+
+        let absolute = Heatmap::from_difference(&target, &current, ErrorMetric::Absolute);
+        assert_eq!(vec![25u64, 15, 25, 35, 25, 15, 25, 35], absolute);
+
+        let squared = Heatmap::from_difference(&target, &current, ErrorMetric::Squared);
+        assert_eq!(vec![225u64, 125, 225, 525, 225, 125, 225, 525], squared);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_rejects_images_of_different_dimensions() {
+        let target = RgbaImage::new(4, 2);
+        let current = RgbaImage::new(2, 4);
+
+        Heatmap::from_difference(&target, &current, ErrorMetric::Squared);
+    }
+}
@@ -0,0 +1,326 @@
+use super::{Heat, Heatmap};
+
+/// Defines how the pixels lying outside of a heatmap are read when a filter
+/// reaches its edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeMode {
+    /// The pixels outside of the heatmap take the heat of the closest edge.
+    Clamp,
+
+    /// The heatmap is repeated, as if it was tiling the plane.
+    Wrap,
+
+    /// The pixels outside of the heatmap are cold.
+    Zero,
+}
+
+impl EdgeMode {
+    /// Returns the index of the pixel read at the given position along an axis
+    /// of the given length, or `None` if it's a cold pixel.
+    pub(crate) fn resolve(self, position: i64, length: usize) -> Option<usize> {
+        let length = length as i64;
+
+        match self {
+            EdgeMode::Clamp => Some(position.clamp(0, length - 1) as usize),
+            EdgeMode::Wrap => Some(position.rem_euclid(length) as usize),
+            EdgeMode::Zero if (0..length).contains(&position) => Some(position as usize),
+            EdgeMode::Zero => None,
+        }
+    }
+}
+
+impl<T: Heat> Heatmap<T> {
+    /// Instanciates a copy of the current heatmap blurred by a gaussian of
+    /// the given standard deviation, in pixels. The kernel is truncated at
+    /// three standard deviations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use libgeometrize::images::{EdgeMode, Heatmap, Importance};
+    ///
+    /// let image = RgbaImage::from_fn(64, 64, |x, y| Rgba([(x * y) as u8, 0, 0, 255]));
+    ///
+    /// // Smoothing the spiky response of the edge detection
+    /// let heatmap = Heatmap::from_image(&image, Importance::Sobel);
+    /// let heatmap = heatmap.gaussian_blur(2.0, EdgeMode::Clamp);
+    /// ```
+    pub fn gaussian_blur(&self, sigma: f64, edges: EdgeMode) -> Self {
+        if sigma <= 0.0 {
+            return self.clone();
+        }
+
+        let radius = (3.0 * sigma).ceil() as i64;
+        let kernel: Vec<f64> = (-radius..=radius)
+            .map(|i| (-((i * i) as f64) / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let total: f64 = kernel.iter().sum();
+        let kernel: Vec<f64> = kernel.iter().map(|weight| weight / total).collect();
+
+        self.convolve_separable(&kernel, &kernel, edges)
+    }
+
+    /// Instanciates a copy of the current heatmap where each pixel is the mean
+    /// of the square window of the given radius around it.
+    pub fn box_blur(&self, radius: u32, edges: EdgeMode) -> Self {
+        let kernel = vec![1.0 / (2 * radius + 1) as f64; (2 * radius + 1) as usize];
+
+        self.convolve_separable(&kernel, &kernel, edges)
+    }
+
+    /// Instanciates a copy of the current heatmap where each pixel is the
+    /// hottest pixel of the square window of the given radius around it.
+    pub fn dilate(&self, radius: u32, edges: EdgeMode) -> Self {
+        let hottest = |a: f64, b: f64| if b > a { b } else { a };
+
+        self.separable(radius, edges, f64::NEG_INFINITY, hottest)
+    }
+
+    /// Instanciates a copy of the current heatmap where each pixel is the
+    /// coldest pixel of the square window of the given radius around it.
+    pub fn erode(&self, radius: u32, edges: EdgeMode) -> Self {
+        let coldest = |a: f64, b: f64| if b < a { b } else { a };
+
+        self.separable(radius, edges, f64::INFINITY, coldest)
+    }
+
+    /// Instanciates a copy of the current heatmap convolved by an arbitrary
+    /// kernel, whose center is the pixel `(width / 2, height / 2)` of the
+    /// kernel. Heats are rounded to the nearest value for integer cells.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::{EdgeMode, Heatmap};
+    ///
+    /// // A Laplacian kernel, which is null on linear ramps
+    /// let kernel = Heatmap::from_fn(3, 3, |x, y| match (x, y) {
+    ///     (1, 1) => 4.0,
+    ///     (1, _) | (_, 1) => -1.0,
+    ///     _ => 0.0,
+    /// });
+    ///
+    /// let ramp = Heatmap::from_fn(8, 8, |x, _| x as f64);
+    /// let laplacian = ramp.convolve(&kernel, EdgeMode::Wrap);
+    /// assert_eq!(Some(&0.0), laplacian.get_pixel(4, 4));
+    /// ```
+    pub fn convolve(&self, kernel: &Heatmap<f64>, edges: EdgeMode) -> Self {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let (center_x, center_y) = (kernel.width() as i64 / 2, kernel.height() as i64 / 2);
+        let heat = self.to_f64_vec();
+
+        Heatmap::from_fn(self.width(), self.height(), |x, y| {
+            let mut sum = 0.0;
+            for ky in 0..kernel.height() {
+                let sy = edges.resolve(y as i64 + center_y - ky as i64, height);
+
+                for kx in 0..kernel.width() {
+                    let sx = edges.resolve(x as i64 + center_x - kx as i64, width);
+
+                    if let (Some(sx), Some(sy)) = (sx, sy) {
+                        sum += kernel.get_pixel(kx, ky).unwrap() * heat[sy * width + sx];
+                    }
+                }
+            }
+
+            T::from_f64(sum)
+        })
+    }
+
+    /// Instanciates a copy of the current heatmap convolved by a separable
+    /// kernel, given by its horizontal and vertical components whose centers
+    /// are their middle element. This is far cheaper than [`convolve`] with
+    /// the equivalent 2D kernel.
+    ///
+    /// [`convolve`]: #method.convolve
+    pub fn convolve_separable(
+        &self,
+        horizontal: &[f64],
+        vertical: &[f64],
+        edges: EdgeMode,
+    ) -> Self {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+
+        let heat = self.to_f64_vec();
+        let heat = convolve_1d(&heat, width, height, horizontal, true, edges);
+        let heat = convolve_1d(&heat, width, height, vertical, false, edges);
+
+        Heatmap::from_fn(self.width(), self.height(), |x, y| {
+            T::from_f64(heat[y as usize * width + x as usize])
+        })
+    }
+
+    /// Instanciates a copy of the current heatmap where each pixel is reduced
+    /// from the square window of the given radius around it, first along the
+    /// rows then along the columns.
+    fn separable<F>(&self, radius: u32, edges: EdgeMode, initial: f64, reduce: F) -> Self
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let radius = radius as i64;
+
+        let pass = |heat: &[f64], horizontal: bool| {
+            let mut result = Vec::with_capacity(heat.len());
+            for y in 0..height {
+                for x in 0..width {
+                    let value = (-radius..=radius).fold(initial, |acc, offset| {
+                        let value = if horizontal {
+                            edges
+                                .resolve(x as i64 + offset, width)
+                                .map(|sx| heat[y * width + sx])
+                        } else {
+                            edges
+                                .resolve(y as i64 + offset, height)
+                                .map(|sy| heat[sy * width + x])
+                        };
+
+                        reduce(acc, value.unwrap_or(0.0))
+                    });
+                    result.push(value);
+                }
+            }
+            result
+        };
+
+        let heat = pass(&self.to_f64_vec(), true);
+        let heat = pass(&heat, false);
+
+        Heatmap::from_fn(self.width(), self.height(), |x, y| {
+            T::from_f64(heat[y as usize * width + x as usize])
+        })
+    }
+
+    /// Returns the heat of all the pixels as floating point numbers.
+    fn to_f64_vec(&self) -> Vec<f64> {
+        self.as_raw().iter().map(|heat| heat.to_f64()).collect()
+    }
+}
+
+/// Convolves the rows or the columns of a map by a one-dimensional kernel
+/// whose center is its middle element.
+fn convolve_1d(
+    heat: &[f64],
+    width: usize,
+    height: usize,
+    kernel: &[f64],
+    horizontal: bool,
+    edges: EdgeMode,
+) -> Vec<f64> {
+    let center = (kernel.len() / 2) as i64;
+
+    let mut result = Vec::with_capacity(heat.len());
+    for y in 0..height {
+        for x in 0..width {
+            let sum: f64 = kernel
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, weight)| {
+                    let offset = center - idx as i64;
+                    let value = if horizontal {
+                        edges
+                            .resolve(x as i64 + offset, width)
+                            .map(|sx| heat[y * width + sx])
+                    } else {
+                        edges
+                            .resolve(y as i64 + offset, height)
+                            .map(|sy| heat[sy * width + x])
+                    };
+
+                    value.map(|value| weight * value)
+                })
+                .sum();
+            result.push(sum);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn impulse(width: u32, height: u32, x: u32, y: u32, heat: f64) -> Heatmap<f64> {
+        Heatmap::from_fn(
+            width,
+            height,
+            |px, py| if (px, py) == (x, y) { heat } else { 0.0 },
+        )
+    }
+
+    #[test]
+    fn it_blurs_a_heatmap_with_a_gaussian() {
+        let heatmap = impulse(15, 15, 7, 7, 1.0).gaussian_blur(1.5, EdgeMode::Zero);
+        let total: f64 = heatmap.as_raw().iter().sum();
+
+        assert!((total - 1.0).abs() < 1e-9);
+        assert_eq!(heatmap.get_pixel(6, 7), heatmap.get_pixel(8, 7));
+        assert_eq!(heatmap.get_pixel(7, 6), heatmap.get_pixel(7, 8));
+        assert!(heatmap.get_pixel(7, 7) > heatmap.get_pixel(6, 7));
+    }
+
+    #[test]
+    fn it_blurs_a_heatmap_with_a_box() {
+        let heatmap: Heatmap = Heatmap::from_fn(3, 3, |x, y| if (x, y) == (1, 1) { 90 } else { 0 });
+
+        for edges in [EdgeMode::Clamp, EdgeMode::Wrap, EdgeMode::Zero] {
+            assert_eq!(vec![10u64; 9], heatmap.box_blur(1, edges));
+        }
+    }
+
+    #[test]
+    fn it_handles_the_edges_of_a_heatmap() {
+        let heatmap: Heatmap = Heatmap::from_fn(4, 1, |x, _| [30, 0, 0, 60][x as usize]);
+        let kernel = [1.0 / 3.0; 3];
+        let identity = [1.0];
+
+        let blur = |edges| heatmap.convolve_separable(&kernel, &identity, edges);
+
+        assert_eq!(vec![20u64, 10, 20, 40], blur(EdgeMode::Clamp));
+        assert_eq!(vec![30u64, 10, 20, 30], blur(EdgeMode::Wrap));
+        assert_eq!(vec![10u64, 10, 20, 20], blur(EdgeMode::Zero));
+    }
+
+    #[test]
+    fn it_dilates_and_erodes_a_heatmap() {
+        let heatmap: Heatmap =
+            Heatmap::from_fn(
+                5,
+                5,
+                |x, y| if (1..4).contains(&x) && y == 2 { 7 } else { 1 },
+            );
+
+        let dilated = heatmap.dilate(1, EdgeMode::Clamp);
+        assert_eq!(Some(&7), dilated.get_pixel(0, 1));
+        assert_eq!(Some(&1), dilated.get_pixel(2, 0));
+
+        let eroded = heatmap.erode(1, EdgeMode::Clamp);
+        assert_eq!(vec![1u64; 25], eroded);
+        assert_eq!(Some(&0), heatmap.erode(1, EdgeMode::Zero).get_pixel(0, 2));
+    }
+
+    #[test]
+    fn it_convolves_a_heatmap_with_an_arbitrary_kernel() {
+        // This kernel moves the heat one pixel to the right and one pixel down
+        let kernel = impulse(3, 3, 2, 2, 1.0);
+        let heatmap = impulse(4, 4, 1, 1, 5.0).convolve(&kernel, EdgeMode::Zero);
+
+        assert_eq!(impulse(4, 4, 2, 2, 5.0), heatmap);
+    }
+
+    #[test]
+    fn it_agrees_with_the_separable_convolution() {
+        let heatmap = Heatmap::from_fn(6, 5, |x, y| ((x * 7 + y * 3) % 5) as f64);
+        let (horizontal, vertical) = ([1.0, 2.0, -1.0], [0.5, 0.0, 2.0]);
+        let kernel = Heatmap::from_fn(3, 3, |x, y| horizontal[x as usize] * vertical[y as usize]);
+
+        let separable = heatmap.convolve_separable(&horizontal, &vertical, EdgeMode::Wrap);
+        let full = heatmap.convolve(&kernel, EdgeMode::Wrap);
+
+        for (a, b) in separable.as_raw().iter().zip(full.as_raw()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+}
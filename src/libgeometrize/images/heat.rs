@@ -0,0 +1,167 @@
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Defines the numeric types which can be stored in the cells of a heatmap,
+/// which are the unsigned integers and the floating point numbers.
+pub trait Heat:
+    Copy
+    + Debug
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    /// Converts the heat into a floating point number.
+    fn to_f64(self) -> f64;
+
+    /// Converts a floating point number into a heat. Integers are rounded to
+    /// the nearest value and saturate at their numeric bounds.
+    fn from_f64(value: f64) -> Self;
+
+    /// Sums two heats, saturating at the numeric bounds instead of overflowing.
+    fn saturating_add(self, other: Self) -> Self;
+
+    /// Subtracts two heats, saturating at the numeric bounds instead of
+    /// overflowing.
+    fn saturating_sub(self, other: Self) -> Self;
+
+    /// Multiplies two heats, saturating at the numeric bounds instead of
+    /// overflowing.
+    fn saturating_mul(self, other: Self) -> Self;
+
+    /// Sums two heats, returning `None` if the sum overflows.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Subtracts two heats, returning `None` if the difference overflows.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// Divides two heats, returning `None` if the divisor is zero or if the
+    /// quotient overflows.
+    fn checked_div(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_integer_heat {
+    ($($t:ty),*) => {
+        $(
+            impl Heat for $t {
+                #[inline]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                #[inline]
+                fn from_f64(value: f64) -> Self {
+                    value.round() as $t
+                }
+
+                #[inline]
+                fn saturating_add(self, other: Self) -> Self {
+                    <$t>::saturating_add(self, other)
+                }
+
+                #[inline]
+                fn saturating_sub(self, other: Self) -> Self {
+                    <$t>::saturating_sub(self, other)
+                }
+
+                #[inline]
+                fn saturating_mul(self, other: Self) -> Self {
+                    <$t>::saturating_mul(self, other)
+                }
+
+                #[inline]
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                #[inline]
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                #[inline]
+                fn checked_div(self, other: Self) -> Option<Self> {
+                    <$t>::checked_div(self, other)
+                }
+            }
+        )*
+    };
+}
+
+/// Floating point numbers are considered overflowing when they become infinite.
+macro_rules! impl_float_heat {
+    ($($t:ty),*) => {
+        $(
+            impl Heat for $t {
+                #[inline]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                #[inline]
+                fn from_f64(value: f64) -> Self {
+                    value as $t
+                }
+
+                #[inline]
+                fn saturating_add(self, other: Self) -> Self {
+                    (self + other).clamp(<$t>::MIN, <$t>::MAX)
+                }
+
+                #[inline]
+                fn saturating_sub(self, other: Self) -> Self {
+                    (self - other).clamp(<$t>::MIN, <$t>::MAX)
+                }
+
+                #[inline]
+                fn saturating_mul(self, other: Self) -> Self {
+                    (self * other).clamp(<$t>::MIN, <$t>::MAX)
+                }
+
+                #[inline]
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    Some(self + other).filter(|sum| sum.is_finite())
+                }
+
+                #[inline]
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    Some(self - other).filter(|difference| difference.is_finite())
+                }
+
+                #[inline]
+                fn checked_div(self, other: Self) -> Option<Self> {
+                    Some(self / other).filter(|quotient| quotient.is_finite())
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_heat!(u8, u16, u32, u64);
+impl_float_heat!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_heats_from_floating_point_numbers() {
+        assert_eq!(3u8, u8::from_f64(2.6));
+        assert_eq!(255u8, u8::from_f64(300.0));
+        assert_eq!(0u16, u16::from_f64(-4.0));
+        assert_eq!(0.5f32, f32::from_f64(0.5));
+    }
+
+    #[test]
+    fn it_detects_overflowing_heats() {
+        assert_eq!(None, Heat::checked_add(250u8, 10));
+        assert_eq!(255u8, Heat::saturating_add(250u8, 10));
+        assert_eq!(None, Heat::checked_add(f32::MAX, f32::MAX));
+        assert_eq!(f32::MAX, Heat::saturating_mul(f32::MAX, 2.0));
+        assert_eq!(None, Heat::checked_div(4u16, 0));
+        assert_eq!(None, Heat::checked_div(1.0f64, 0.0));
+        assert_eq!(Some(2.0f64), Heat::checked_div(1.0f64, 0.5));
+    }
+}
@@ -1,9 +1,77 @@
-use std::ops::{Add, AddAssign};
-use image::{GrayImage, ImageBuffer, Luma};
+use super::{Colormap, Heat};
+use image::{GrayImage, ImageBuffer, Luma, Rgb, RgbImage};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+use std::{error, fmt};
+
+/// The minimal width, in pixels, of the colorbar rendered next to a heatmap.
+const COLORBAR_MIN_WIDTH: u32 = 4;
+
+/// The color of the margin separating a heatmap from its colorbar.
+const COLORBAR_MARGIN: Rgb<u8> = Rgb([255, 255, 255]);
+
+/// Defines the errors which can happen while combining or transforming
+/// heatmaps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeatmapError {
+    /// The heatmaps don't have the same dimensions.
+    DimensionMismatch {
+        expected: (u32, u32),
+        found: (u32, u32),
+    },
+
+    /// The heat of a pixel overflowed.
+    Overflow,
+
+    /// The dimensions of a heatmap, or its number of pixels, overflowed.
+    DimensionOverflow,
+
+    /// The heat of the pixels was divided by zero.
+    DivisionByZero,
+}
+
+impl fmt::Display for HeatmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeatmapError::DimensionMismatch { expected, found } => write!(
+                f,
+                "expected a {}x{} heatmap, found a {}x{} one",
+                expected.0, expected.1, found.0, found.1
+            ),
+            HeatmapError::Overflow => write!(f, "the heat of a pixel overflowed"),
+            HeatmapError::DimensionOverflow => write!(f, "the heatmap dimensions overflowed"),
+            HeatmapError::DivisionByZero => write!(f, "the heat was divided by zero"),
+        }
+    }
+}
+
+impl error::Error for HeatmapError {}
+
+/// Defines how the heat of a heatmap is mapped between `0.0` and `1.0` when
+/// it's exported as an image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Normalization {
+    /// The heat is divided by the maximal heat of the heatmap.
+    Max,
+
+    /// The heat is divided by the heat of the given percentile, between `0.0`
+    /// and `100.0`, the hotter pixels being clipped. This keeps a few very hot
+    /// pixels from flattening the rest of the heatmap.
+    Percentile(f64),
+
+    /// The heat is linearly mapped from a fixed range, the pixels outside of
+    /// it being clipped. This keeps several heatmaps comparable.
+    Range { min: f64, max: f64 },
+
+    /// The logarithm of the heat is divided by the logarithm of the maximal
+    /// heat of the heatmap, which reveals the dimmest regions. Negative heats
+    /// are considered as zero.
+    Logarithmic,
+}
 
 /// A heatmap is a 2D canvas that shows magnitude of a phenomenon as colors.
-/// This data structure is storing the magnitude in a matrix of `u64` words
-/// and can be exported as grayscale image encoded on 8-bits or 16-bits.
+/// This data structure is storing the magnitude in a matrix of [`Heat`] cells,
+/// which are `u64` words by default, and can be exported as grayscale image
+/// encoded on 8-bits or 16-bits, or as a colored image thanks to a [`Colormap`].
 ///
 /// # Example
 ///
@@ -18,19 +86,64 @@ use image::{GrayImage, ImageBuffer, Luma};
 ///         0
 ///    }
 /// });
+///
+/// // Storing floating point weights instead
+/// let weights = Heatmap::from_fn(32, 32, |x, _| x as f64 / 31.0);
 /// ```
 #[derive(Clone, Debug)]
-pub struct Heatmap {
-    inner: Vec<u64>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawHeatmap<T>"))]
+pub struct Heatmap<T: Heat = u64> {
+    inner: Vec<T>,
     width: u32,
     height: u32,
 }
 
-impl Heatmap {
+/// The serialized layout of a heatmap, whose cells must be checked against
+/// its dimensions before it's turned into a [`Heatmap`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawHeatmap<T> {
+    inner: Vec<T>,
+    width: u32,
+    height: u32,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Heat> std::convert::TryFrom<RawHeatmap<T>> for Heatmap<T> {
+    type Error = String;
+
+    fn try_from(raw: RawHeatmap<T>) -> Result<Self, Self::Error> {
+        let expected = raw
+            .width
+            .checked_mul(raw.height)
+            .map(|count| count as usize);
+        if expected != Some(raw.inner.len()) {
+            return Err(format!(
+                "a {}x{} heatmap can't have {} cells",
+                raw.width,
+                raw.height,
+                raw.inner.len()
+            ));
+        }
+
+        Ok(Self {
+            inner: raw.inner,
+            width: raw.width,
+            height: raw.height,
+        })
+    }
+}
+
+impl<T: Heat> Heatmap<T> {
     /// Instanciates a new heatmap with the given dimensions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of pixels of the heatmap doesn't fit in a `u32`.
     pub fn new(width: u32, height: u32) -> Self {
         Self {
-            inner: vec![0; (width * height) as usize],
+            inner: vec![T::default(); pixel_count(width, height)],
             width,
             height,
         }
@@ -38,12 +151,16 @@ impl Heatmap {
 
     /// Instanciates a new heatmap with the given dimensions and fill it with
     /// the values produced by a user-provided closure. This closure is taking
-    /// `x` and `y` coordinates as arguments and must return a `u64` word.
+    /// `x` and `y` coordinates as arguments and must return a heat.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of pixels of the heatmap doesn't fit in a `u32`.
     pub fn from_fn<F>(width: u32, height: u32, f: F) -> Self
     where
-        F: Fn(u32, u32) -> u64
+        F: Fn(u32, u32) -> T,
     {
-        let mut inner = vec![0; (width * height) as usize];
+        let mut inner = vec![T::default(); pixel_count(width, height)];
         for idx in 0..inner.capacity() {
             let x = idx as u32 % width;
             let y = idx as u32 / width;
@@ -79,12 +196,12 @@ impl Heatmap {
     pub fn clear(&mut self) {
         self.inner.clear();
         self.inner.shrink_to_fit();
-        self.inner = vec![0; (self.width * self.height) as usize];
+        self.inner = vec![T::default(); (self.width * self.height) as usize];
     }
 
     /// Retrieves a reference to a pixel contained in the heatmap thanks
     /// to user-supplied coordinates.
-    pub fn get_pixel(&self, x: u32, y: u32) -> Option<&u64> {
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<&T> {
         if x < self.width && y < self.height {
             Some(&self.inner[(y * self.width + x) as usize])
         } else {
@@ -94,7 +211,7 @@ impl Heatmap {
 
     /// Retrieves a mutable reference to a pixel contained in the heatmap
     /// thanks to user-supplied coordinates.
-    pub fn get_pixel_mut(&mut self, x: u32, y: u32) -> Option<&mut u64> {
+    pub fn get_pixel_mut(&mut self, x: u32, y: u32) -> Option<&mut T> {
         if x < self.width && y < self.height {
             Some(&mut self.inner[(y * self.width + x) as usize])
         } else {
@@ -102,83 +219,406 @@ impl Heatmap {
         }
     }
 
+    /// Returns the heat of all the pixels of the heatmap, row by row.
+    #[inline]
+    pub fn as_raw(&self) -> &[T] {
+        &self.inner
+    }
+
+    /// Instanciates a copy of the current heatmap whose cells are converted
+    /// into another type of heat. Converting into integers rounds the heat to
+    /// the nearest value, saturating at their numeric bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::Heatmap;
+    ///
+    /// let weights = Heatmap::from_fn(2, 1, |x, _| 200.0 * x as f64 + 0.4);
+    /// assert_eq!(vec![0u8, 200], weights.convert::<u8>());
+    /// ```
+    pub fn convert<U: Heat>(&self) -> Heatmap<U> {
+        Heatmap {
+            inner: self
+                .inner
+                .iter()
+                .map(|heat| U::from_f64(heat.to_f64()))
+                .collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Returns the heat of all the pixels of the heatmap, row by row, once
+    /// normalized between `0.0` and `1.0` with the given normalization mode.
+    ///
+    /// An all-zero heatmap is normalized into zeros, whatever the mode.
+    pub fn normalized(&self, normalization: Normalization) -> Vec<f64> {
+        let (low, high) = match normalization {
+            Normalization::Max | Normalization::Logarithmic => {
+                (0.0, self.max().map_or(0.0, |(_, heat)| heat.to_f64()))
+            }
+            Normalization::Percentile(percentile) => {
+                (0.0, self.percentile(percentile).map_or(0.0, Heat::to_f64))
+            }
+            Normalization::Range { min, max } => (min, max),
+        };
+
+        let scale = |heat: f64, low: f64, high: f64| {
+            if high <= low {
+                if heat > low {
+                    1.0
+                } else {
+                    0.0
+                }
+            } else {
+                ((heat - low) / (high - low)).clamp(0.0, 1.0)
+            }
+        };
+
+        self.inner
+            .iter()
+            .map(|heat| match normalization {
+                Normalization::Logarithmic => scale(
+                    heat.to_f64().max(0.0).ln_1p(),
+                    low.max(0.0).ln_1p(),
+                    high.max(0.0).ln_1p(),
+                ),
+                _ => scale(heat.to_f64(), low, high),
+            })
+            .collect()
+    }
+
     /// Instanciates a copy of the current heatmap converted into a 8-bits grayscale
-    /// image with a supplied `gamma` factor.
+    /// image with a supplied `gamma` factor, the heat being normalized by its
+    /// maximal value.
     pub fn to_luma8(&self, gamma: f64) -> GrayImage {
-        let max_heat = self.max_heat() as f64;
+        self.to_luma8_normalized(Normalization::Max, gamma)
+    }
+
+    /// Instanciates a copy of the current heatmap converted into a 8-bits grayscale
+    /// image with the given normalization mode and a supplied `gamma` factor.
+    pub fn to_luma8_normalized(&self, normalization: Normalization, gamma: f64) -> GrayImage {
+        let heat = self.normalized(normalization);
 
         GrayImage::from_fn(self.width, self.height, |x, y| {
-            let px = self.inner[(y * self.width + x) as usize] as f64 / max_heat;
-            let px = px.powf(gamma);
-            Luma([(255.0 * px) as u8])
+            let px = heat[(y * self.width + x) as usize].powf(gamma);
+            Luma([(u8::MAX as f64 * px).round() as u8])
         })
     }
 
     /// Instanciates a copy of the current heatmap converted into a 16-bits grayscale
-    /// image with a supplied `gamma` factor.
+    /// image with a supplied `gamma` factor, the heat being normalized by its
+    /// maximal value.
     pub fn to_luma16(&self, gamma: f64) -> ImageBuffer<Luma<u16>, Vec<u16>> {
-        let max_heat = self.max_heat() as f64;
+        self.to_luma16_normalized(Normalization::Max, gamma)
+    }
+
+    /// Instanciates a copy of the current heatmap converted into a 16-bits grayscale
+    /// image with the given normalization mode and a supplied `gamma` factor.
+    pub fn to_luma16_normalized(
+        &self,
+        normalization: Normalization,
+        gamma: f64,
+    ) -> ImageBuffer<Luma<u16>, Vec<u16>> {
+        let heat = self.normalized(normalization);
 
         ImageBuffer::<Luma<u16>, Vec<u16>>::from_fn(self.width, self.height, |x, y| {
-            let px = self.inner[(y * self.width + x) as usize] as f64 / max_heat;
-            let px = px.powf(gamma);
-            Luma([(255.0 * px) as u16])
+            let px = heat[(y * self.width + x) as usize].powf(gamma);
+            Luma([(u16::MAX as f64 * px).round() as u16])
         })
     }
 
-    fn max_heat(&self) -> u64 {
-        self.inner
+    /// Instanciates a copy of the current heatmap converted into a RGB image
+    /// with a supplied `gamma` factor, the heat being normalized by its maximal
+    /// value and colored by a colormap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::{Colormap, Heatmap};
+    ///
+    /// let heatmap = Heatmap::from_fn(32, 32, |x, y| (x * y) as u64);
+    /// let image = heatmap.to_rgb8(1.0, &Colormap::Viridis);
+    /// ```
+    pub fn to_rgb8(&self, gamma: f64, colormap: &Colormap) -> RgbImage {
+        self.to_rgb8_normalized(Normalization::Max, gamma, colormap)
+    }
+
+    /// Instanciates a copy of the current heatmap converted into a RGB image
+    /// with the given normalization mode and a supplied `gamma` factor, the
+    /// heat being colored by a colormap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::{Colormap, Heatmap, Normalization};
+    ///
+    /// // A few very hot pixels shouldn't flatten the rest of the heatmap
+    /// let heatmap = Heatmap::from_fn(32, 32, |x, y| if x == y { 1000 } else { x as u64 });
+    /// let image = heatmap.to_rgb8_normalized(Normalization::Percentile(95.0), 1.0, &Colormap::Magma);
+    /// ```
+    pub fn to_rgb8_normalized(
+        &self,
+        normalization: Normalization,
+        gamma: f64,
+        colormap: &Colormap,
+    ) -> RgbImage {
+        let heat = self.normalized(normalization);
+
+        RgbImage::from_fn(self.width, self.height, |x, y| {
+            colormap.color(heat[(y * self.width + x) as usize].powf(gamma))
+        })
+    }
+
+    /// Instanciates a copy of the current heatmap converted into a RGB image
+    /// like [`Heatmap::to_rgb8`], with a colorbar legend rendered on its right.
+    pub fn to_rgb8_with_colorbar(&self, gamma: f64, colormap: &Colormap) -> RgbImage {
+        let heatmap = self.to_rgb8(gamma, colormap);
+        let bar_width = (self.width / 16).max(COLORBAR_MIN_WIDTH);
+        let offset = self.width + bar_width / 2;
+        let colorbar = colormap.colorbar(bar_width, self.height);
+
+        RgbImage::from_fn(offset + bar_width, self.height, |x, y| {
+            if x < self.width {
+                *heatmap.get_pixel(x, y)
+            } else if x >= offset {
+                *colorbar.get_pixel(x - offset, y)
+            } else {
+                COLORBAR_MARGIN
+            }
+        })
+    }
+
+    /// Instanciates a new heatmap keeping the hottest heat of two heatmaps for
+    /// each pixel of their intersection.
+    pub fn maximum(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| if b > a { b } else { a })
+    }
+
+    /// Instanciates a new heatmap keeping the coldest heat of two heatmaps for
+    /// each pixel of their intersection.
+    pub fn minimum(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| if b < a { b } else { a })
+    }
+
+    /// Sums two heatmaps pixel by pixel over their intersection, the heat
+    /// saturating at the numeric bounds instead of overflowing.
+    pub fn saturating_add(&self, other: &Self) -> Self {
+        self.zip_with(other, T::saturating_add)
+    }
+
+    /// Subtracts two heatmaps pixel by pixel over their intersection, the heat
+    /// saturating at the numeric bounds instead of overflowing. Unsigned
+    /// integer cells therefore saturate at zero, while floating point cells
+    /// can hold negative heats.
+    pub fn saturating_sub(&self, other: &Self) -> Self {
+        self.zip_with(other, T::saturating_sub)
+    }
+
+    /// Multiplies the heat of all the pixels by a scalar, the heat saturating
+    /// at the numeric bounds instead of overflowing.
+    pub fn saturating_mul(&self, factor: T) -> Self {
+        Self {
+            inner: self
+                .inner
+                .iter()
+                .map(|heat| T::saturating_mul(*heat, factor))
+                .collect(),
+            ..*self
+        }
+    }
+
+    /// Sums two heatmaps of the same dimensions pixel by pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the heatmaps have different dimensions or if the
+    /// sum of two pixels overflows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::{Heatmap, HeatmapError};
+    ///
+    /// let a = Heatmap::from_fn(2, 2, |x, _| x as u64);
+    /// let b = Heatmap::from_fn(2, 2, |_, y| y as u64);
+    /// assert_eq!(vec![0u64, 1, 1, 2], a.checked_add(&b).unwrap());
+    ///
+    /// let c = Heatmap::new(4, 4);
+    /// assert!(matches!(a.checked_add(&c), Err(HeatmapError::DimensionMismatch { .. })));
+    /// ```
+    pub fn checked_add(&self, other: &Self) -> Result<Self, HeatmapError> {
+        self.try_zip_with(other, T::checked_add)
+    }
+
+    /// Subtracts two heatmaps of the same dimensions pixel by pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the heatmaps have different dimensions or if the
+    /// difference of two pixels overflows.
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, HeatmapError> {
+        self.try_zip_with(other, T::checked_sub)
+    }
+
+    /// Divides the heat of all the pixels by a scalar.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the divisor is zero or if the quotient of a pixel
+    /// overflows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::{Heatmap, HeatmapError};
+    ///
+    /// let heatmap = Heatmap::from_fn(2, 1, |x, _| x as u64 * 4 + 2);
+    /// assert_eq!(vec![1u64, 3], heatmap.checked_div(2).unwrap());
+    /// assert_eq!(Err(HeatmapError::DivisionByZero), heatmap.checked_div(0));
+    /// ```
+    pub fn checked_div(&self, divisor: T) -> Result<Self, HeatmapError> {
+        if divisor == T::default() {
+            return Err(HeatmapError::DivisionByZero);
+        }
+
+        let inner = self
+            .inner
             .iter()
-            .fold(0, |max, px| {
-                if *px > max {
-                    *px
-                } else {
-                    max
-                }
-            })
+            .map(|heat| T::checked_div(*heat, divisor))
+            .collect::<Option<_>>()
+            .ok_or(HeatmapError::Overflow)?;
+
+        Ok(Self { inner, ..*self })
+    }
+
+    /// Instanciates a new heatmap by combining the pixels of two heatmaps over
+    /// their intersection.
+    fn zip_with<F>(&self, other: &Self, f: F) -> Self
+    where
+        F: Fn(T, T) -> T,
+    {
+        let width = self.width.min(other.width);
+        let height = self.height.min(other.height);
+
+        Heatmap::from_fn(width, height, |x, y| {
+            f(
+                self.inner[(y * self.width + x) as usize],
+                other.inner[(y * other.width + x) as usize],
+            )
+        })
+    }
+
+    /// Instanciates a new heatmap by combining the pixels of two heatmaps of
+    /// the same dimensions with a fallible operation.
+    fn try_zip_with<F>(&self, other: &Self, f: F) -> Result<Self, HeatmapError>
+    where
+        F: Fn(T, T) -> Option<T>,
+    {
+        if self.dimensions() != other.dimensions() {
+            return Err(HeatmapError::DimensionMismatch {
+                expected: self.dimensions(),
+                found: other.dimensions(),
+            });
+        }
+
+        let inner = self
+            .inner
+            .iter()
+            .zip(&other.inner)
+            .map(|(a, b)| f(*a, *b))
+            .collect::<Option<_>>()
+            .ok_or(HeatmapError::Overflow)?;
+
+        Ok(Self { inner, ..*self })
     }
 }
 
-impl Add for Heatmap {
+impl<T: Heat> Add for Heatmap<T> {
     type Output = Self;
 
+    /// Sums two heatmaps pixel by pixel over their intersection, the heat
+    /// saturating instead of overflowing as with [`Heatmap::saturating_add`].
+    ///
+    /// The pixels of heatmaps of different dimensions lying outside of their
+    /// intersection are dropped. Use [`Heatmap::checked_add`] to reject such
+    /// heatmaps instead.
     fn add(self, other: Self) -> Self::Output {
-        let width = std::cmp::min(self.width, other.width);
-        let height = std::cmp::min(self.height, other.height);
+        self.saturating_add(&other)
+    }
+}
 
-        let mut inner = vec![0;  (width * height) as usize];
-        for i in 0..inner.capacity() {
-            inner[i] = self.inner[i] + other.inner[i];
-        }
+impl<T: Heat> AddAssign for Heatmap<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = self.saturating_add(&other);
+    }
+}
 
-        Self {
-            inner,
-            width,
-            height,
-        }
+impl<T: Heat> Sub for Heatmap<T> {
+    type Output = Self;
+
+    /// Subtracts two heatmaps pixel by pixel over their intersection, the heat
+    /// saturating instead of overflowing as with [`Heatmap::saturating_sub`].
+    ///
+    /// The pixels of heatmaps of different dimensions lying outside of their
+    /// intersection are dropped. Use [`Heatmap::checked_sub`] to reject such
+    /// heatmaps instead.
+    fn sub(self, other: Self) -> Self::Output {
+        self.saturating_sub(&other)
     }
 }
 
-impl AddAssign for Heatmap {
-    fn add_assign(&mut self, other: Self) {
-        let width = std::cmp::min(self.width, other.width);
-        let height = std::cmp::min(self.height, other.height);
+impl<T: Heat> SubAssign for Heatmap<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = self.saturating_sub(&other);
+    }
+}
 
-        let mut inner = vec![0;  (width * height) as usize];
-        for i in 0..inner.capacity() {
-            inner[i] = self.inner[i] + other.inner[i];
-        }
+impl<T: Heat> Mul<T> for Heatmap<T> {
+    type Output = Self;
 
-        *self = Self {
-            inner,
-            width,
-            height
-        };
+    /// Multiplies the heat of all the pixels by a scalar, the heat saturating
+    /// instead of overflowing as with [`Heatmap::saturating_mul`].
+    fn mul(mut self, factor: T) -> Self::Output {
+        self *= factor;
+        self
+    }
+}
+
+impl<T: Heat> MulAssign<T> for Heatmap<T> {
+    fn mul_assign(&mut self, factor: T) {
+        self.inner
+            .iter_mut()
+            .for_each(|heat| *heat = T::saturating_mul(*heat, factor));
+    }
+}
+
+impl<T: Heat> Div<T> for Heatmap<T> {
+    type Output = Self;
+
+    /// Divides the heat of all the pixels by a scalar.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heat is an integer and the divisor is zero. Use
+    /// [`Heatmap::checked_div`] to handle a zero divisor instead.
+    fn div(mut self, divisor: T) -> Self::Output {
+        self /= divisor;
+        self
     }
 }
 
-impl Eq for Heatmap {}
-impl PartialEq for Heatmap {
+impl<T: Heat> DivAssign<T> for Heatmap<T> {
+    fn div_assign(&mut self, divisor: T) {
+        self.inner
+            .iter_mut()
+            .for_each(|heat| *heat = *heat / divisor);
+    }
+}
+
+impl<T: Heat + Eq> Eq for Heatmap<T> {}
+impl<T: Heat> PartialEq for Heatmap<T> {
     fn eq(&self, other: &Self) -> bool {
         if self.width != other.width || self.height != other.height {
             return false;
@@ -194,28 +634,30 @@ impl PartialEq for Heatmap {
     }
 }
 
-impl PartialEq<Vec<u64>> for Heatmap {
-    fn eq(&self, other: &Vec<u64>) -> bool {
+impl<T: Heat> PartialEq<Vec<T>> for Heatmap<T> {
+    fn eq(&self, other: &Vec<T>) -> bool {
         if self.inner.len() != other.len() {
             return false;
         }
 
-        for i in 0..self.inner.len() {
-            if self.inner[i] != other[i] {
-                return false
-            }
-        }
-
-        true
+        self.inner.iter().zip(other).all(|(a, b)| a == b)
     }
 }
 
-impl PartialEq<Heatmap> for Vec<u64> {
-    fn eq(&self, other: &Heatmap) -> bool {
+impl<T: Heat> PartialEq<Heatmap<T>> for Vec<T> {
+    fn eq(&self, other: &Heatmap<T>) -> bool {
         *other == *self
     }
 }
 
+/// Returns the number of pixels of a heatmap of the given dimensions. Pixels
+/// are indexed with `u32` coordinates, so their number must fit in a `u32`.
+fn pixel_count(width: u32, height: u32) -> usize {
+    width
+        .checked_mul(height)
+        .expect("the heatmap dimensions overflowed") as usize
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,6 +668,23 @@ mod tests {
         assert_eq!(vec![1u64, 1, 0, 1], heatmap);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_can_serialize_a_heatmap() {
+        let heatmap = Heatmap::from_fn(3, 2, |x, y| (x + y) as u64);
+
+        let json = serde_json::to_string(&heatmap).unwrap();
+        assert_eq!(heatmap, serde_json::from_str::<Heatmap>(&json).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_rejects_a_truncated_heatmap() {
+        let json = r#"{"inner":[0,1,2,1,2],"width":3,"height":2}"#;
+
+        assert!(serde_json::from_str::<Heatmap>(json).is_err());
+    }
+
     #[test]
     fn it_can_sum_heatmaps() {
         let a = Heatmap::from_fn(2, 2, |x, y| (4 * (x + y)) as u64);
@@ -233,4 +692,183 @@ mod tests {
 
         assert_eq!(vec![0u64, 5, 5, 10], a + b);
     }
+
+    #[test]
+    fn it_sums_heatmaps_of_different_dimensions_over_their_intersection() {
+        let a = Heatmap::from_fn(3, 2, |x, y| (10 * y + x) as u64);
+        let b = Heatmap::from_fn(2, 3, |x, y| (100 * y + x) as u64);
+
+        let sum = a.clone() + b.clone();
+        assert_eq!((2, 2), sum.dimensions());
+        assert_eq!(vec![0u64, 2, 110, 112], sum);
+
+        let mut c = b;
+        c += a;
+        assert_eq!(sum, c);
+    }
+
+    #[test]
+    fn it_combines_heatmaps() {
+        let a = Heatmap::from_fn(2, 2, |x, y| (4 * (x + y)) as u64);
+        let b = Heatmap::from_fn(2, 2, |x, _| 3 * x as u64);
+
+        assert_eq!(vec![0u64, 1, 4, 5], a.clone() - b.clone());
+        assert_eq!(vec![0u64, 12, 12, 24], a.clone() * 3);
+        assert_eq!(vec![0u64, 1, 1, 2], a.clone() / 3);
+        assert_eq!(vec![0u64, 4, 4, 8], a.maximum(&b));
+        assert_eq!(vec![0u64, 3, 0, 3], a.minimum(&b));
+        assert_eq!(vec![0u64, 0, 0, 0], b.saturating_sub(&a));
+    }
+
+    #[test]
+    fn it_saturates_instead_of_overflowing() {
+        let a = Heatmap::from_fn(2, 1, |x, _| u64::MAX - x as u64);
+        let b = Heatmap::from_fn(2, 1, |_, _| 1);
+
+        assert_eq!(vec![u64::MAX, u64::MAX], a.saturating_add(&b));
+        assert_eq!(vec![u64::MAX, u64::MAX], a.saturating_mul(2));
+    }
+
+    #[test]
+    fn it_saturates_the_arithmetic_operators() {
+        let a = Heatmap::from_fn(2, 1, |x, _| u64::MAX - x as u64);
+        let b = Heatmap::from_fn(2, 1, |_, _| 1);
+
+        assert_eq!(vec![u64::MAX, u64::MAX], a.clone() + b.clone());
+        assert_eq!(vec![0u64, 0], b.clone() - a.clone());
+        assert_eq!(vec![u64::MAX, u64::MAX], a.clone() * 2);
+
+        let mut c = b.clone();
+        c -= a;
+        c += b;
+        assert_eq!(vec![1u64, 1], c);
+    }
+
+    #[test]
+    fn it_checks_the_sum_of_heatmaps() {
+        let a = Heatmap::from_fn(2, 1, |x, _| u64::MAX - x as u64);
+        let b = Heatmap::from_fn(2, 1, |_, _| 1);
+
+        assert_eq!(Err(HeatmapError::Overflow), a.checked_add(&b));
+        assert_eq!(Err(HeatmapError::Overflow), b.checked_sub(&a));
+        assert_eq!(vec![0u64, 0], b.checked_sub(&b).unwrap());
+        assert_eq!(
+            Err(HeatmapError::DimensionMismatch {
+                expected: (2, 1),
+                found: (1, 2)
+            }),
+            a.checked_add(&Heatmap::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn it_checks_the_division_of_heatmaps() {
+        let a: Heatmap<u8> = Heatmap::from_fn(2, 1, |x, _| x as u8 * 10);
+        let b: Heatmap<f32> = Heatmap::from_fn(2, 1, |x, _| x as f32);
+
+        assert_eq!(vec![0u8, 5], a.checked_div(2).unwrap());
+        assert_eq!(Err(HeatmapError::DivisionByZero), a.checked_div(0));
+        assert_eq!(Err(HeatmapError::DivisionByZero), b.checked_div(0.0));
+        assert_eq!(Err(HeatmapError::Overflow), b.checked_div(1e-40));
+    }
+
+    #[test]
+    #[should_panic(expected = "the heatmap dimensions overflowed")]
+    fn it_rejects_heatmaps_with_too_many_pixels() {
+        let _: Heatmap = Heatmap::new(1 << 16, 1 << 16);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_dividing_an_integer_heatmap_by_zero() {
+        let _ = Heatmap::from_fn(2, 1, |x, _| x as u64) / 0;
+    }
+
+    #[test]
+    fn it_exports_the_full_range_of_16_bits() {
+        let heatmap = Heatmap::from_fn(3, 1, |x, _| x as u64);
+        let image = heatmap.to_luma16(1.0);
+
+        assert_eq!(vec![0u16, 32768, 65535], image.into_raw());
+        assert_eq!(vec![0u8, 128, 255], heatmap.to_luma8(1.0).into_raw());
+    }
+
+    #[test]
+    fn it_exports_an_all_zero_heatmap() {
+        let heatmap: Heatmap = Heatmap::new(2, 2);
+
+        for normalization in [
+            Normalization::Max,
+            Normalization::Percentile(50.0),
+            Normalization::Range { min: 0.0, max: 0.0 },
+            Normalization::Logarithmic,
+        ] {
+            assert_eq!(vec![0.0; 4], heatmap.normalized(normalization));
+        }
+        assert_eq!(vec![0u16; 4], heatmap.to_luma16(1.0).into_raw());
+        assert_eq!(vec![0u8; 4], heatmap.to_luma8(1.0).into_raw());
+    }
+
+    #[test]
+    fn it_normalizes_a_heatmap() {
+        let heatmap: Heatmap = Heatmap::from_fn(5, 1, |x, _| [0, 1, 3, 7, 100][x as usize]);
+
+        assert_eq!(
+            vec![0.0, 0.01, 0.03, 0.07, 1.0],
+            heatmap.normalized(Normalization::Max)
+        );
+        assert_eq!(
+            vec![0.0, 1.0 / 7.0, 3.0 / 7.0, 1.0, 1.0],
+            heatmap.normalized(Normalization::Percentile(75.0))
+        );
+        assert_eq!(
+            vec![0.0, 0.0, 0.25, 0.75, 1.0],
+            heatmap.normalized(Normalization::Range { min: 1.0, max: 9.0 })
+        );
+
+        let log = heatmap.normalized(Normalization::Logarithmic);
+        assert!((log[3] - 3.0 / 101_f64.log2()).abs() < 1e-9);
+        assert_eq!(1.0, log[4]);
+    }
+
+    #[test]
+    fn it_exports_heatmaps_of_any_cell_type() {
+        let weights = Heatmap::from_fn(3, 1, |x, _| x as f32 * 0.25);
+        let counts = Heatmap::from_fn(3, 1, |x, _| x as u8);
+
+        assert_eq!(vec![0u8, 128, 255], weights.to_luma8(1.0).into_raw());
+        assert_eq!(vec![0u16, 32768, 65535], counts.to_luma16(1.0).into_raw());
+    }
+
+    #[test]
+    fn it_converts_heatmaps_between_cell_types() {
+        let weights = Heatmap::from_fn(2, 2, |x, y| x as f64 * 0.6 + y as f64 * 300.0);
+
+        assert_eq!(vec![0u8, 1, 255, 255], weights.convert::<u8>());
+        assert_eq!(vec![0u16, 1, 300, 301], weights.convert::<u16>());
+        assert_eq!(
+            vec![0.0, 1.0, 300.0, 301.0],
+            weights.convert::<u64>().convert::<f64>()
+        );
+    }
+
+    #[test]
+    fn it_can_export_a_colored_heatmap() {
+        let heatmap = Heatmap::from_fn(4, 2, |x, _| x as u64);
+        let image = heatmap.to_rgb8(1.0, &Colormap::Inferno);
+
+        assert_eq!(&Colormap::Inferno.color(0.0), image.get_pixel(0, 1));
+        assert_eq!(&Colormap::Inferno.color(1.0), image.get_pixel(3, 0));
+    }
+
+    #[test]
+    fn it_can_export_a_colored_heatmap_with_a_colorbar() {
+        let heatmap = Heatmap::from_fn(64, 32, |x, _| x as u64);
+        let image = heatmap.to_rgb8_with_colorbar(1.0, &Colormap::Viridis);
+
+        assert_eq!((70, 32), image.dimensions());
+        assert_eq!(&COLORBAR_MARGIN, image.get_pixel(65, 0));
+        assert_eq!(&Colormap::Viridis.color(1.0), image.get_pixel(69, 0));
+        assert_eq!(&Colormap::Viridis.color(0.0), image.get_pixel(66, 31));
+    }
 }
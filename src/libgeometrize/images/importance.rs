@@ -0,0 +1,355 @@
+use super::{EdgeMode, Heatmap, Resampling, SummedAreaTable};
+use image::RgbaImage;
+use std::f64::consts::PI;
+
+/// The size of the longest side of the image once downscaled to compute its
+/// spectral residual.
+const SPECTRAL_RESIDUAL_SIZE: u32 = 64;
+
+/// The standard deviation, in pixels of the downscaled image, of the gaussian
+/// smoothing the spectral residual saliency.
+const SPECTRAL_RESIDUAL_SIGMA: f64 = 2.5;
+
+/// The heat given to the most salient pixel by the spectral residual measure.
+const SPECTRAL_RESIDUAL_SCALE: f64 = u16::MAX as f64;
+
+/// Defines the measures which can be used to build a heatmap showing the
+/// important regions of an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Importance {
+    /// The magnitude of the gradient of the luminance, computed with the Sobel
+    /// operator. It highlights the edges of the image.
+    Sobel,
+
+    /// The variance of the luminance in a square window of the given radius
+    /// around each pixel. It highlights the textured regions of the image.
+    LocalVariance { radius: u32 },
+
+    /// The spectral residual saliency from Hou and Zhang, which highlights the
+    /// regions of the image standing out of their surroundings. The heat is
+    /// scaled between `0` and `65535`.
+    SpectralResidual,
+}
+
+impl Heatmap {
+    /// Instanciates a new heatmap showing the important regions of an image,
+    /// according to the given measure. Measures are rounded to the nearest
+    /// integer to be stored in the heatmap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use libgeometrize::images::{Heatmap, Importance};
+    ///
+    /// let image = RgbaImage::from_fn(32, 32, |x, _| {
+    ///     if x < 16 {
+    ///         Rgba([0, 0, 0, 255])
+    ///     } else {
+    ///         Rgba([255, 255, 255, 255])
+    ///     }
+    /// });
+    ///
+    /// let heatmap = Heatmap::from_image(&image, Importance::Sobel);
+    /// assert_eq!(Some(&0), heatmap.get_pixel(4, 4));
+    /// assert!(*heatmap.get_pixel(16, 4).unwrap() > 0);
+    ///
+    /// let preview = heatmap.to_luma8(1.0);
+    /// ```
+    pub fn from_image(image: &RgbaImage, importance: Importance) -> Self {
+        let (width, height) = image.dimensions();
+        let luma = luminance(image);
+
+        let values = match importance {
+            Importance::Sobel => sobel(&luma, width, height),
+            Importance::LocalVariance { radius } => local_variance(&luma, width, height, radius),
+            Importance::SpectralResidual => spectral_residual(&luma, width, height),
+        };
+
+        Heatmap::from_fn(width, height, |x, y| {
+            values[(y * width + x) as usize].max(0.0).round() as u64
+        })
+    }
+}
+
+/// Returns the luminance of each pixel of an image, between 0 and 255.
+fn luminance(image: &RgbaImage) -> Vec<f64> {
+    image
+        .pixels()
+        .map(|px| 0.299 * px[0] as f64 + 0.587 * px[1] as f64 + 0.114 * px[2] as f64)
+        .collect()
+}
+
+/// Computes the magnitude of the gradient of a luminance map with the Sobel
+/// operator, the borders being extended.
+fn sobel(luma: &[f64], width: u32, height: u32) -> Vec<f64> {
+    let (w, h) = (width as i64, height as i64);
+    let at = |x: i64, y: i64| luma[(y.clamp(0, h - 1) * w + x.clamp(0, w - 1)) as usize];
+
+    let mut values = Vec::with_capacity(luma.len());
+    for y in 0..h {
+        for x in 0..w {
+            let gx = at(x + 1, y - 1) + 2.0 * at(x + 1, y) + at(x + 1, y + 1)
+                - at(x - 1, y - 1)
+                - 2.0 * at(x - 1, y)
+                - at(x - 1, y + 1);
+            let gy = at(x - 1, y + 1) + 2.0 * at(x, y + 1) + at(x + 1, y + 1)
+                - at(x - 1, y - 1)
+                - 2.0 * at(x, y - 1)
+                - at(x + 1, y - 1);
+
+            values.push((gx * gx + gy * gy).sqrt());
+        }
+    }
+
+    values
+}
+
+/// Computes the variance of a luminance map in a square window around each
+/// pixel, the window being clipped to the map. Summed-area tables of the
+/// luminance and of its square make each window constant time.
+fn local_variance(luma: &[f64], width: u32, height: u32, radius: u32) -> Vec<f64> {
+    let at = |x: u32, y: u32| luma[(y * width + x) as usize];
+    let sums = SummedAreaTable::new(&Heatmap::from_fn(width, height, at));
+    let squares =
+        SummedAreaTable::new(&Heatmap::from_fn(width, height, |x, y| at(x, y) * at(x, y)));
+
+    let mut values = Vec::with_capacity(luma.len());
+    for y in 0..height {
+        for x in 0..width {
+            let (x1, y1) = (x.saturating_sub(radius), y.saturating_sub(radius));
+            let side = |start: u32, position: u32| {
+                (position.saturating_add(radius) - start).saturating_add(1)
+            };
+            let (w, h) = (side(x1, x), side(y1, y));
+            let mean = sums.mean(x1, y1, w, h);
+
+            values.push((squares.mean(x1, y1, w, h) - mean * mean).max(0.0));
+        }
+    }
+
+    values
+}
+
+/// Computes the spectral residual saliency of a luminance map: the map is
+/// downscaled, the log-amplitude of its spectrum is smoothed and subtracted to
+/// itself, and the residual is transformed back, smoothed and upscaled. The
+/// amplitude is shifted by one before taking its logarithm so that frequencies
+/// missing from the image don't dominate the residual.
+fn spectral_residual(luma: &[f64], width: u32, height: u32) -> Vec<f64> {
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let scale = (SPECTRAL_RESIDUAL_SIZE as f64 / width.max(height) as f64).min(1.0);
+    let sw = ((width as f64 * scale).round() as usize).max(1);
+    let sh = ((height as f64 * scale).round() as usize).max(1);
+    let (sw32, sh32) = (sw as u32, sh as u32);
+    let small = Heatmap::from_fn(width, height, |x, y| luma[(y * width + x) as usize])
+        .resize(sw32, sh32, Resampling::Area)
+        .expect("the thumbnail fits in a heatmap");
+
+    let mut re = small.as_raw().to_vec();
+    let mut im = vec![0.0; sw * sh];
+    dft_2d(&mut re, &mut im, sw, sh, false);
+
+    let amplitude = Heatmap::from_fn(sw32, sh32, |x, y| {
+        let idx = y as usize * sw + x as usize;
+        re[idx].hypot(im[idx]).ln_1p()
+    });
+    // The spectrum is periodic, and so is its smoothing
+    let smoothed = amplitude.box_blur(1, EdgeMode::Wrap);
+    let (amplitude, smoothed) = (amplitude.as_raw(), smoothed.as_raw());
+
+    for idx in 0..re.len() {
+        let phase = im[idx].atan2(re[idx]);
+        let magnitude = (amplitude[idx] - smoothed[idx]).exp();
+        re[idx] = magnitude * phase.cos();
+        im[idx] = magnitude * phase.sin();
+    }
+    dft_2d(&mut re, &mut im, sw, sh, true);
+
+    let saliency = Heatmap::from_fn(sw32, sh32, |x, y| {
+        let idx = y as usize * sw + x as usize;
+        re[idx] * re[idx] + im[idx] * im[idx]
+    })
+    .gaussian_blur(SPECTRAL_RESIDUAL_SIGMA, EdgeMode::Clamp)
+    .resize(width, height, Resampling::Bilinear)
+    .expect("the image fits in a heatmap");
+    let saliency = saliency.as_raw();
+
+    let max = saliency.iter().cloned().fold(0.0, f64::max);
+    if max <= 0.0 {
+        return saliency.to_vec();
+    }
+
+    saliency
+        .iter()
+        .map(|value| value / max * SPECTRAL_RESIDUAL_SCALE)
+        .collect()
+}
+
+/// Computes in place the discrete Fourier transform of a complex map, or its
+/// inverse, by transforming its rows then its columns.
+fn dft_2d(re: &mut [f64], im: &mut [f64], width: usize, height: usize, inverse: bool) {
+    let mut row_re = vec![0.0; width.max(height)];
+    let mut row_im = vec![0.0; width.max(height)];
+
+    for y in 0..height {
+        let range = y * width..(y + 1) * width;
+        row_re[..width].copy_from_slice(&re[range.clone()]);
+        row_im[..width].copy_from_slice(&im[range.clone()]);
+        dft(
+            &row_re[..width],
+            &row_im[..width],
+            &mut re[range.clone()],
+            &mut im[range],
+            inverse,
+        );
+    }
+
+    let mut column_re = vec![0.0; height];
+    let mut column_im = vec![0.0; height];
+    for x in 0..width {
+        for y in 0..height {
+            row_re[y] = re[y * width + x];
+            row_im[y] = im[y * width + x];
+        }
+        dft(
+            &row_re[..height],
+            &row_im[..height],
+            &mut column_re,
+            &mut column_im,
+            inverse,
+        );
+        for y in 0..height {
+            re[y * width + x] = column_re[y];
+            im[y * width + x] = column_im[y];
+        }
+    }
+}
+
+/// Computes the discrete Fourier transform of a complex signal, or its
+/// inverse which is normalized by the length of the signal.
+fn dft(re: &[f64], im: &[f64], out_re: &mut [f64], out_im: &mut [f64], inverse: bool) {
+    let n = re.len();
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let norm = if inverse { 1.0 / n as f64 } else { 1.0 };
+
+    for k in 0..n {
+        let (mut sum_re, mut sum_im) = (0.0, 0.0);
+        for t in 0..n {
+            let angle = sign * 2.0 * PI * ((k * t) % n) as f64 / n as f64;
+            let (sin, cos) = angle.sin_cos();
+            sum_re += re[t] * cos - im[t] * sin;
+            sum_im += re[t] * sin + im[t] * cos;
+        }
+        out_re[k] = sum_re * norm;
+        out_im[k] = sum_im * norm;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn it_detects_edges_with_the_sobel_operator() {
+        let image = RgbaImage::from_fn(8, 4, |x, _| {
+            if x < 4 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+
+        let heatmap = Heatmap::from_image(&image, Importance::Sobel);
+
+        assert_eq!(Some(&0), heatmap.get_pixel(1, 1));
+        assert_eq!(Some(&1020), heatmap.get_pixel(3, 1));
+        assert_eq!(Some(&1020), heatmap.get_pixel(4, 1));
+        assert_eq!(Some(&0), heatmap.get_pixel(6, 1));
+    }
+
+    #[test]
+    fn it_computes_the_local_variance() {
+        let image = RgbaImage::from_fn(16, 16, |x, y| {
+            if x >= 8 && (x + y) % 2 == 0 {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([0, 0, 0, 255])
+            }
+        });
+
+        let heatmap = Heatmap::from_image(&image, Importance::LocalVariance { radius: 1 });
+
+        assert_eq!(Some(&0), heatmap.get_pixel(2, 8));
+        // A 3x3 checkerboard window has 4 or 5 white pixels out of 9
+        let variance: f64 = 255.0 * 255.0 * 20.0 / 81.0;
+        assert_eq!(Some(&(variance.round() as u64)), heatmap.get_pixel(12, 8));
+    }
+
+    #[test]
+    fn it_clips_the_local_variance_window_to_the_image() {
+        let image = RgbaImage::from_fn(4, 4, |x, _| {
+            if x < 2 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+
+        // The window covers the whole image, half black and half white
+        let heatmap = Heatmap::from_image(&image, Importance::LocalVariance { radius: u32::MAX });
+        let variance: f64 = 255.0 * 255.0 / 4.0;
+
+        assert!(heatmap
+            .as_raw()
+            .iter()
+            .all(|heat| *heat == variance.round() as u64));
+    }
+
+    #[test]
+    fn it_inverts_the_discrete_fourier_transform() {
+        let signal: Vec<f64> = (0..12).map(|i| (i * i % 7) as f64).collect();
+        let mut re = signal.clone();
+        let mut im = vec![0.0; 12];
+
+        dft_2d(&mut re, &mut im, 4, 3, false);
+        dft_2d(&mut re, &mut im, 4, 3, true);
+
+        for (a, b) in signal.iter().zip(&re) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        assert!(im.iter().all(|v| v.abs() < 1e-9));
+    }
+
+    #[test]
+    fn it_highlights_salient_regions_with_the_spectral_residual() {
+        // A bright blob standing out of a dim textured background
+        let image = RgbaImage::from_fn(64, 64, |x, y| {
+            if (40..48).contains(&x) && (16..24).contains(&y) {
+                Rgba([255, 255, 255, 255])
+            } else {
+                let value = 64 + ((x * 7 + y * 13) * 31 % 17) as u8;
+                Rgba([value, value, value, 255])
+            }
+        });
+
+        let heatmap = Heatmap::from_image(&image, Importance::SpectralResidual);
+        let (mut best, mut hottest) = ((0, 0), 0);
+        for y in 0..64 {
+            for x in 0..64 {
+                let heat = *heatmap.get_pixel(x, y).unwrap();
+                if heat > hottest {
+                    best = (x, y);
+                    hottest = heat;
+                }
+            }
+        }
+
+        assert_eq!(SPECTRAL_RESIDUAL_SCALE as u64, hottest);
+        assert!((36..52).contains(&best.0) && (12..28).contains(&best.1));
+    }
+}
@@ -0,0 +1,201 @@
+use super::{Heat, Heatmap};
+use crate::math::shapes::Shape;
+
+/// A summed-area table, also known as an integral image, built from a heatmap.
+/// Once built, the total heat inside any axis-aligned rectangle is read in
+/// constant time, and inside any shape in a time proportional to its height.
+///
+/// # Example
+///
+/// ```
+/// use libgeometrize::images::{Heatmap, SummedAreaTable};
+/// use libgeometrize::math::shapes::Rectangle;
+///
+/// // Only the right half of the heatmap is important
+/// let heatmap: Heatmap = Heatmap::from_fn(64, 64, |x, _| if x < 32 { 0 } else { 1 });
+/// let table = SummedAreaTable::new(&heatmap);
+///
+/// assert_eq!(32.0 * 64.0, table.sum(0, 0, 64, 64));
+///
+/// // Rejecting a candidate shape landing in the unimportant half
+/// let rect = Rectangle::new().origin(8.0, 8.0).aspect(16.0, 8.0).angle(0.3).build();
+/// assert_eq!(0.0, table.mean_shape(&rect));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SummedAreaTable {
+    sums: Vec<f64>,
+    width: u32,
+    height: u32,
+}
+
+impl SummedAreaTable {
+    /// Instanciates a new summed-area table from the heat of a heatmap.
+    pub fn new<T: Heat>(heatmap: &Heatmap<T>) -> Self {
+        let (width, height) = heatmap.dimensions();
+        let stride = width as usize + 1;
+
+        let mut sums = vec![0.0; stride * (height as usize + 1)];
+        for (y, row) in heatmap.as_raw().chunks(width.max(1) as usize).enumerate() {
+            let mut row_sum = 0.0;
+            for (x, heat) in row.iter().enumerate() {
+                row_sum += heat.to_f64();
+                sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row_sum;
+            }
+        }
+
+        Self {
+            sums,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the dimensions of the heatmap the table was built from.
+    #[inline]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the total heat inside the rectangle whose top-left corner and
+    /// dimensions are given. The rectangle is clipped to the heatmap.
+    pub fn sum(&self, x: u32, y: u32, width: u32, height: u32) -> f64 {
+        let (x1, y1) = (x.min(self.width) as usize, y.min(self.height) as usize);
+        let x2 = x.saturating_add(width).min(self.width) as usize;
+        let y2 = y.saturating_add(height).min(self.height) as usize;
+        let stride = self.width as usize + 1;
+
+        self.sums[y2 * stride + x2] - self.sums[y1 * stride + x2] - self.sums[y2 * stride + x1]
+            + self.sums[y1 * stride + x1]
+    }
+
+    /// Returns the mean heat inside the rectangle whose top-left corner and
+    /// dimensions are given, once clipped to the heatmap, or `0.0` if it
+    /// doesn't cover any pixel.
+    pub fn mean(&self, x: u32, y: u32, width: u32, height: u32) -> f64 {
+        let width = width.min(self.width.saturating_sub(x));
+        let height = height.min(self.height.saturating_sub(y));
+
+        if width == 0 || height == 0 {
+            return 0.0;
+        }
+
+        self.sum(x, y, width, height) / (width as f64 * height as f64)
+    }
+
+    /// Returns the total heat of the pixels covered by a shape, which is
+    /// decomposed into the horizontal strips of its rasterization.
+    pub fn sum_shape<S: Shape + ?Sized>(&self, shape: &S) -> f64 {
+        self.strips(shape).map(|(_, sum)| sum).sum()
+    }
+
+    /// Returns the mean heat of the pixels covered by a shape, or `0.0` if it
+    /// doesn't cover any pixel. The shape is decomposed into the horizontal
+    /// strips of its rasterization.
+    pub fn mean_shape<S: Shape + ?Sized>(&self, shape: &S) -> f64 {
+        let (pixels, sum) = self
+            .strips(shape)
+            .fold((0, 0.0), |(pixels, total), (count, sum)| {
+                (pixels + count, total + sum)
+            });
+
+        if pixels == 0 {
+            return 0.0;
+        }
+
+        sum / pixels as f64
+    }
+
+    /// Returns the number of pixels and the total heat of each horizontal
+    /// strip covered by a shape.
+    fn strips<'a, S: Shape + ?Sized>(&'a self, shape: &S) -> impl Iterator<Item = (u32, f64)> + 'a {
+        shape
+            .rasterize(self.width, self.height)
+            .into_iter()
+            .map(move |line| {
+                let count = line.x2 - line.x1 + 1;
+                (count, self.sum(line.x1, line.y, count, 1))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::shapes::{Rectangle, Triangle};
+    use crate::math::Point;
+
+    fn heatmap() -> Heatmap<u32> {
+        Heatmap::from_fn(23, 17, |x, y| (x * 7 + y * 13) % 11)
+    }
+
+    /// Sums the heat of the pixels covered by a shape, one pixel at a time.
+    fn brute_force<S: Shape>(heatmap: &Heatmap<u32>, shape: &S) -> (u32, f64) {
+        shape
+            .rasterize(heatmap.width(), heatmap.height())
+            .iter()
+            .flat_map(|line| (line.x1..=line.x2).map(move |x| (x, line.y)))
+            .fold((0, 0.0), |(count, sum), (x, y)| {
+                (count + 1, sum + *heatmap.get_pixel(x, y).unwrap() as f64)
+            })
+    }
+
+    #[test]
+    fn it_sums_the_heat_of_rectangles() {
+        let heatmap = heatmap();
+        let table = SummedAreaTable::new(&heatmap);
+
+        for (x, y, width, height) in [(0, 0, 23, 17), (3, 4, 5, 6), (10, 0, 1, 17), (7, 7, 0, 3)] {
+            let expected: f64 = (y..y + height)
+                .flat_map(|py| (x..x + width).map(move |px| (px, py)))
+                .map(|(px, py)| *heatmap.get_pixel(px, py).unwrap() as f64)
+                .sum();
+
+            assert_eq!(expected, table.sum(x, y, width, height));
+        }
+    }
+
+    #[test]
+    fn it_clips_rectangles_to_the_heatmap() {
+        let heatmap: Heatmap = Heatmap::from_fn(4, 4, |_, _| 2);
+        let table = SummedAreaTable::new(&heatmap);
+
+        assert_eq!(8.0, table.sum(2, 2, 10, 10));
+        assert_eq!(2.0, table.mean(2, 2, 10, 10));
+        assert_eq!(0.0, table.sum(5, 5, 2, 2));
+        assert_eq!(0.0, table.mean(5, 5, 2, 2));
+        assert_eq!(32.0, table.sum(0, 0, u32::MAX, u32::MAX));
+    }
+
+    #[test]
+    fn it_averages_the_heat_of_rotated_shapes() {
+        let heatmap = heatmap();
+        let table = SummedAreaTable::new(&heatmap);
+
+        let rect = Rectangle::new()
+            .origin(8.0, 2.0)
+            .aspect(10.0, 6.0)
+            .angle(0.6)
+            .build();
+        let triangle = Triangle::new(
+            Point::new(2.0, 3.0),
+            Point::new(20.0, 8.0),
+            Point::new(6.0, 15.0),
+        );
+
+        let (count, sum) = brute_force(&heatmap, &rect);
+        assert_eq!(sum, table.sum_shape(&rect));
+        assert!((sum / count as f64 - table.mean_shape(&rect)).abs() < 1e-9);
+
+        let (count, sum) = brute_force(&heatmap, &triangle);
+        assert_eq!(sum, table.sum_shape(&triangle));
+        assert!((sum / count as f64 - table.mean_shape(&triangle)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_builds_the_table_of_an_empty_heatmap() {
+        let table = SummedAreaTable::new(&Heatmap::<u8>::new(0, 5));
+
+        assert_eq!((0, 5), table.dimensions());
+        assert_eq!(0.0, table.sum(0, 0, 4, 4));
+    }
+}
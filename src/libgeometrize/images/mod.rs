@@ -1,3 +1,26 @@
+mod canvas;
+mod color;
+mod colormap;
+mod density;
+mod difference;
+mod filter;
+mod heat;
 mod heatmap;
+mod importance;
+mod integral;
+mod resample;
+mod sampler;
+mod statistics;
 
-pub use heatmap::Heatmap;
+pub use canvas::Canvas;
+pub use color::{average_color, optimal_color};
+pub use colormap::{Colormap, Gradient};
+pub use difference::{difference_full, difference_partial, ErrorMetric};
+pub use filter::EdgeMode;
+pub use heat::Heat;
+pub use heatmap::{Heatmap, HeatmapError, Normalization};
+pub use importance::Importance;
+pub use integral::SummedAreaTable;
+pub use resample::Resampling;
+pub use sampler::HeatmapSampler;
+pub use statistics::Histogram;
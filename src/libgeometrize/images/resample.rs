@@ -0,0 +1,288 @@
+use super::{EdgeMode, Heat, Heatmap, HeatmapError};
+
+/// Defines how the heat of a heatmap is interpolated when it's resized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resampling {
+    /// Each pixel takes the heat of the closest pixel of the source heatmap.
+    Nearest,
+
+    /// Each pixel linearly interpolates the heat of the four closest pixels of
+    /// the source heatmap, which gives smooth results when upscaling.
+    Bilinear,
+
+    /// Each pixel takes the mean heat of the area of the source heatmap it
+    /// covers, which keeps the mean heat when downscaling.
+    Area,
+}
+
+impl<T: Heat> Heatmap<T> {
+    /// Instanciates a copy of the current heatmap resized to the given
+    /// dimensions. Heats are rounded to the nearest value for integer cells.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of pixels of the resized heatmap
+    /// overflows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use libgeometrize::images::{Heatmap, Importance, Resampling};
+    ///
+    /// // Computing an importance map on a thumbnail of the target image...
+    /// let thumbnail = RgbaImage::from_fn(32, 32, |x, y| Rgba([(x * y) as u8, 0, 0, 255]));
+    /// let heatmap = Heatmap::from_image(&thumbnail, Importance::Sobel);
+    ///
+    /// // ... to drive an approximation at the full resolution
+    /// let heatmap = heatmap.resize(256, 256, Resampling::Bilinear).unwrap();
+    /// assert_eq!((256, 256), heatmap.dimensions());
+    /// ```
+    pub fn resize(
+        &self,
+        width: u32,
+        height: u32,
+        resampling: Resampling,
+    ) -> Result<Self, HeatmapError> {
+        if width.checked_mul(height).is_none() {
+            return Err(HeatmapError::DimensionOverflow);
+        }
+
+        if self.width() == 0 || self.height() == 0 {
+            return Ok(Heatmap::new(width, height));
+        }
+
+        let (source_width, source_height) = (self.width() as usize, self.height() as usize);
+        let heat = |x: usize, y: usize| self.as_raw()[y * source_width + x].to_f64();
+
+        Ok(match resampling {
+            Resampling::Nearest => {
+                let xs = nearest_indices(source_width, width as usize);
+                let ys = nearest_indices(source_height, height as usize);
+
+                Heatmap::from_fn(width, height, |x, y| {
+                    *self
+                        .get_pixel(xs[x as usize] as u32, ys[y as usize] as u32)
+                        .unwrap()
+                })
+            }
+            Resampling::Bilinear | Resampling::Area => {
+                let weights = if resampling == Resampling::Bilinear {
+                    bilinear_weights
+                } else {
+                    area_weights
+                };
+                let xs = weights(source_width, width as usize);
+                let ys = weights(source_height, height as usize);
+
+                Heatmap::from_fn(width, height, |x, y| {
+                    let mut sum = 0.0;
+                    for (sy, wy) in &ys[y as usize] {
+                        for (sx, wx) in &xs[x as usize] {
+                            sum += wx * wy * heat(*sx, *sy);
+                        }
+                    }
+
+                    T::from_f64(sum)
+                })
+            }
+        })
+    }
+
+    /// Instanciates a copy of the region of the current heatmap whose top-left
+    /// corner and dimensions are given. The region is clipped to the heatmap.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let width = width.min(self.width().saturating_sub(x));
+        let height = height.min(self.height().saturating_sub(y));
+
+        Heatmap::from_fn(width, height, |px, py| {
+            *self.get_pixel(x + px, y + py).unwrap()
+        })
+    }
+
+    /// Instanciates a copy of the current heatmap surrounded by margins of the
+    /// given sizes, whose heat is defined by the edge mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the dimensions of the padded heatmap, or its number
+    /// of pixels, overflow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::{EdgeMode, Heatmap};
+    ///
+    /// let heatmap: Heatmap = Heatmap::from_fn(2, 1, |x, _| x as u64 + 1);
+    ///
+    /// assert_eq!(vec![0u64, 1, 2, 0], heatmap.pad(1, 0, 1, 0, EdgeMode::Zero).unwrap());
+    /// assert_eq!(vec![1u64, 1, 2, 2], heatmap.pad(1, 0, 1, 0, EdgeMode::Clamp).unwrap());
+    /// assert_eq!(vec![2u64, 1, 2, 1], heatmap.pad(1, 0, 1, 0, EdgeMode::Wrap).unwrap());
+    /// ```
+    pub fn pad(
+        &self,
+        left: u32,
+        top: u32,
+        right: u32,
+        bottom: u32,
+        edges: EdgeMode,
+    ) -> Result<Self, HeatmapError> {
+        let width = self
+            .width()
+            .checked_add(left)
+            .and_then(|w| w.checked_add(right));
+        let height = self
+            .height()
+            .checked_add(top)
+            .and_then(|h| h.checked_add(bottom));
+        let (width, height) = match (width, height) {
+            (Some(width), Some(height)) if width.checked_mul(height).is_some() => (width, height),
+            _ => return Err(HeatmapError::DimensionOverflow),
+        };
+
+        if self.width() == 0 || self.height() == 0 {
+            return Ok(Heatmap::new(width, height));
+        }
+
+        Ok(Heatmap::from_fn(width, height, |x, y| {
+            let sx = edges.resolve(x as i64 - left as i64, self.width() as usize);
+            let sy = edges.resolve(y as i64 - top as i64, self.height() as usize);
+
+            match (sx, sy) {
+                (Some(sx), Some(sy)) => *self.get_pixel(sx as u32, sy as u32).unwrap(),
+                _ => T::default(),
+            }
+        }))
+    }
+}
+
+/// Returns, for each pixel along an axis of the destination, the index of the
+/// closest pixel of the source, their centers being aligned.
+fn nearest_indices(source: usize, destination: usize) -> Vec<usize> {
+    (0..destination)
+        .map(|idx| ((idx as f64 + 0.5) * source as f64 / destination as f64) as usize)
+        .map(|idx| idx.min(source - 1))
+        .collect()
+}
+
+/// Returns, for each pixel along an axis of the destination, the two closest
+/// pixels of the source with their interpolation weights, their centers being
+/// aligned and the edges being extended.
+fn bilinear_weights(source: usize, destination: usize) -> Vec<Vec<(usize, f64)>> {
+    (0..destination)
+        .map(|idx| {
+            let position = ((idx as f64 + 0.5) * source as f64 / destination as f64 - 0.5)
+                .clamp(0.0, (source - 1) as f64);
+            let first = position.floor() as usize;
+            let t = position - first as f64;
+
+            vec![(first, 1.0 - t), ((first + 1).min(source - 1), t)]
+        })
+        .collect()
+}
+
+/// Returns, for each pixel along an axis of the destination, the pixels of the
+/// source it overlaps, weighted by the length of their overlap.
+fn area_weights(source: usize, destination: usize) -> Vec<Vec<(usize, f64)>> {
+    let scale = source as f64 / destination as f64;
+
+    (0..destination)
+        .map(|idx| {
+            let (start, end) = (idx as f64 * scale, (idx + 1) as f64 * scale);
+            let last = (end.ceil() as usize).min(source);
+
+            (start.floor() as usize..last)
+                .map(|sx| {
+                    let overlap = end.min((sx + 1) as f64) - start.max(sx as f64);
+                    (sx, overlap / scale)
+                })
+                .filter(|(_, weight)| *weight > 0.0)
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_resizes_a_heatmap_to_the_nearest_pixels() {
+        let heatmap: Heatmap = Heatmap::from_fn(2, 2, |x, y| (x + 2 * y) as u64);
+
+        let upscaled = heatmap.resize(4, 2, Resampling::Nearest).unwrap();
+        assert_eq!(vec![0u64, 0, 1, 1, 2, 2, 3, 3], upscaled);
+        assert_eq!(heatmap, upscaled.resize(2, 2, Resampling::Nearest).unwrap());
+    }
+
+    #[test]
+    fn it_resizes_a_heatmap_with_a_bilinear_interpolation() {
+        let heatmap = Heatmap::from_fn(2, 1, |x, _| x as f64 * 4.0);
+        let upscaled = heatmap.resize(4, 1, Resampling::Bilinear).unwrap();
+
+        assert_eq!(vec![0.0, 1.0, 3.0, 4.0], upscaled);
+    }
+
+    #[test]
+    fn it_resizes_a_heatmap_by_averaging_areas() {
+        let heatmap: Heatmap = Heatmap::from_fn(4, 4, |x, y| (x + 4 * y) as u64 * 4);
+        assert_eq!(
+            vec![10u64, 18, 42, 50],
+            heatmap.resize(2, 2, Resampling::Area).unwrap()
+        );
+
+        // The mean heat is kept with a fractional scale
+        let heatmap = Heatmap::from_fn(5, 3, |x, y| ((x * 7 + y * 3) % 4) as f64);
+        let resized = heatmap.resize(3, 2, Resampling::Area).unwrap();
+        let mean = |h: &Heatmap<f64>| h.as_raw().iter().sum::<f64>() / h.as_raw().len() as f64;
+
+        assert!((mean(&heatmap) - mean(&resized)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_crops_a_heatmap() {
+        let heatmap: Heatmap = Heatmap::from_fn(4, 3, |x, y| (x + 4 * y) as u64);
+
+        assert_eq!(vec![5u64, 6, 9, 10], heatmap.crop(1, 1, 2, 2));
+        assert_eq!((1, 2), heatmap.crop(3, 1, 5, 5).dimensions());
+        assert_eq!((0, 0), heatmap.crop(8, 8, 2, 2).dimensions());
+    }
+
+    #[test]
+    fn it_pads_a_heatmap() {
+        let heatmap: Heatmap = Heatmap::from_fn(2, 2, |x, y| (1 + x + 2 * y) as u64);
+        let padded = heatmap.pad(0, 1, 1, 0, EdgeMode::Clamp).unwrap();
+
+        assert_eq!((3, 3), padded.dimensions());
+        assert_eq!(vec![1u64, 2, 2, 1, 2, 2, 3, 4, 4], padded);
+        assert_eq!(heatmap, padded.crop(0, 1, 2, 2));
+    }
+
+    #[test]
+    fn it_rejects_paddings_overflowing_the_dimensions() {
+        let heatmap: Heatmap = Heatmap::new(2, 2);
+
+        assert_eq!(
+            Err(HeatmapError::DimensionOverflow),
+            heatmap.pad(u32::MAX, 0, 0, 0, EdgeMode::Zero)
+        );
+        assert_eq!(
+            Err(HeatmapError::DimensionOverflow),
+            heatmap.pad(0, 0, 1 << 16, 1 << 16, EdgeMode::Zero)
+        );
+    }
+
+    #[test]
+    fn it_rejects_resizings_overflowing_the_dimensions() {
+        let heatmap: Heatmap = Heatmap::new(2, 2);
+
+        assert_eq!(
+            Err(HeatmapError::DimensionOverflow),
+            heatmap.resize(1 << 16, 1 << 16, Resampling::Nearest)
+        );
+        assert_eq!(
+            Err(HeatmapError::DimensionOverflow),
+            Heatmap::<u64>::new(0, 0).resize(u32::MAX, 2, Resampling::Area)
+        );
+    }
+}
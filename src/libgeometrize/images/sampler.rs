@@ -0,0 +1,168 @@
+use super::{Heat, Heatmap};
+use crate::math::Point;
+use rand::Rng;
+
+/// Samples the pixels of a heatmap with a probability proportional to their
+/// heat. The sampler is built once from a heatmap with the alias method from
+/// Vose, after which each sample is drawn in constant time.
+///
+/// If the heatmap is completely cold, all its pixels are equally likely to be
+/// sampled.
+///
+/// # Example
+///
+/// ```
+/// use libgeometrize::images::{Heatmap, HeatmapSampler};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// // Only the right half of the heatmap is hot
+/// let heatmap: Heatmap = Heatmap::from_fn(32, 32, |x, _| if x < 16 { 0 } else { 1 });
+/// let sampler = HeatmapSampler::new(&heatmap);
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let (x, _) = sampler.sample(&mut rng);
+/// assert!(x >= 16);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeatmapSampler {
+    probabilities: Vec<f64>,
+    aliases: Vec<u32>,
+    width: u32,
+    height: u32,
+}
+
+impl HeatmapSampler {
+    /// Instanciates a new sampler from the heat of the pixels of a heatmap.
+    /// Negative heats are considered as zero.
+    pub fn new<T: Heat>(heatmap: &Heatmap<T>) -> Self {
+        let heat: Vec<f64> = heatmap
+            .as_raw()
+            .iter()
+            .map(|px| px.to_f64().max(0.0))
+            .collect();
+        let count = heat.len();
+        let total: f64 = heat.iter().sum();
+
+        let mut probabilities: Vec<f64> = if total > 0.0 {
+            heat.iter().map(|px| px * count as f64 / total).collect()
+        } else {
+            vec![1.0; count]
+        };
+        let mut aliases: Vec<u32> = (0..count as u32).collect();
+
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..count).partition(|idx| probabilities[*idx] < 1.0);
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            aliases[less] = more as u32;
+            probabilities[more] -= 1.0 - probabilities[less];
+
+            if probabilities[more] < 1.0 {
+                large.pop();
+                small.push(more);
+            }
+        }
+
+        // Because of rounding errors, the remaining pixels may be slightly
+        // below or above 1 while they should be exactly 1.
+        for idx in small.into_iter().chain(large) {
+            probabilities[idx] = 1.0;
+        }
+
+        let (width, height) = heatmap.dimensions();
+        Self {
+            probabilities,
+            aliases,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the dimensions of the sampled heatmap.
+    #[inline]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Draws the coordinates of a pixel of the heatmap thanks to a
+    /// user-supplied RNG.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sampled heatmap is empty.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> (u32, u32) {
+        let idx = rng.gen_range(0..self.probabilities.len());
+        let idx = if rng.gen::<f64>() < self.probabilities[idx] {
+            idx as u32
+        } else {
+            self.aliases[idx]
+        };
+
+        (idx % self.width, idx / self.width)
+    }
+
+    /// Draws a point uniformly located inside a pixel of the heatmap drawn by
+    /// [`sample`], thanks to a user-supplied RNG.
+    ///
+    /// [`sample`]: #method.sample
+    pub fn sample_point<R: Rng + ?Sized>(&self, rng: &mut R) -> Point {
+        let (x, y) = self.sample(rng);
+
+        Point::new(x as f64 + rng.gen::<f64>(), y as f64 + rng.gen::<f64>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn it_samples_pixels_proportionally_to_their_heat() {
+        let heatmap = Heatmap::from_fn(4, 2, |x, y| (x + 4 * y) as u64);
+        let sampler = HeatmapSampler::new(&heatmap);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut counts = [0u32; 8];
+        let samples = 280_000;
+        for _ in 0..samples {
+            let (x, y) = sampler.sample(&mut rng);
+            counts[(y * 4 + x) as usize] += 1;
+        }
+
+        // The total heat is 28, so each unit of heat is worth 10000 samples
+        assert_eq!(0, counts[0]);
+        for (idx, count) in counts.iter().enumerate() {
+            let expected = idx as f64 * 10_000.0;
+            assert!((*count as f64 - expected).abs() <= 0.03 * expected);
+        }
+    }
+
+    #[test]
+    fn it_samples_a_cold_heatmap_uniformly() {
+        let sampler = HeatmapSampler::new(&Heatmap::<u64>::new(3, 3));
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut counts = [0u32; 9];
+        for _ in 0..90_000 {
+            let (x, y) = sampler.sample(&mut rng);
+            counts[(y * 3 + x) as usize] += 1;
+        }
+
+        assert!(counts
+            .iter()
+            .all(|count| (*count as f64 - 10_000.0).abs() < 500.0));
+    }
+
+    #[test]
+    fn it_samples_points_inside_the_hot_pixels() {
+        let heatmap: Heatmap = Heatmap::from_fn(8, 8, |x, y| if (x, y) == (5, 2) { 1 } else { 0 });
+        let sampler = HeatmapSampler::new(&heatmap);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let p = sampler.sample_point(&mut rng);
+            assert!(p.x >= 5.0 && p.x < 6.0 && p.y >= 2.0 && p.y < 3.0);
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use super::{Heat, Heatmap};
+use std::cmp::Ordering;
+
+/// A histogram of the heat of a heatmap, whose buckets evenly split the range
+/// between its coldest and hottest pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    /// The heat of the coldest pixel, which is the lower bound of the first
+    /// bucket.
+    pub min: f64,
+
+    /// The heat of the hottest pixel, which is the upper bound of the last
+    /// bucket.
+    pub max: f64,
+
+    /// The number of pixels falling into each bucket.
+    pub counts: Vec<u64>,
+}
+
+impl Histogram {
+    /// Returns the lower and upper bounds of the bucket at the given index.
+    pub fn bucket_range(&self, idx: usize) -> (f64, f64) {
+        let width = (self.max - self.min) / self.counts.len() as f64;
+
+        (
+            self.min + width * idx as f64,
+            self.min + width * (idx + 1) as f64,
+        )
+    }
+}
+
+impl<T: Heat> Heatmap<T> {
+    /// Returns the total heat of the heatmap.
+    pub fn sum(&self) -> f64 {
+        self.as_raw().iter().map(|heat| heat.to_f64()).sum()
+    }
+
+    /// Returns the mean heat of the pixels of the heatmap, or `0.0` if it's
+    /// empty.
+    pub fn mean(&self) -> f64 {
+        if self.as_raw().is_empty() {
+            return 0.0;
+        }
+
+        self.sum() / self.as_raw().len() as f64
+    }
+
+    /// Returns the variance of the heat of the pixels of the heatmap, or `0.0`
+    /// if it's empty.
+    pub fn variance(&self) -> f64 {
+        if self.as_raw().is_empty() {
+            return 0.0;
+        }
+
+        let mean = self.mean();
+        let squares: f64 = self
+            .as_raw()
+            .iter()
+            .map(|heat| (heat.to_f64() - mean).powi(2))
+            .sum();
+
+        squares / self.as_raw().len() as f64
+    }
+
+    /// Returns the coordinates and the heat of the coldest pixel of the
+    /// heatmap, the first one in reading order if there are several, or
+    /// `None` if the heatmap is empty.
+    pub fn min(&self) -> Option<((u32, u32), T)> {
+        self.extremum(Ordering::Less)
+    }
+
+    /// Returns the coordinates and the heat of the hottest pixel of the
+    /// heatmap, the first one in reading order if there are several, or
+    /// `None` if the heatmap is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::Heatmap;
+    ///
+    /// let heatmap = Heatmap::from_fn(4, 4, |x, y| (x * y) as u64);
+    /// assert_eq!(Some(((3, 3), 9)), heatmap.max());
+    /// ```
+    pub fn max(&self) -> Option<((u32, u32), T)> {
+        self.extremum(Ordering::Greater)
+    }
+
+    /// Returns the heat below which the given percentage of the pixels lie,
+    /// between `0.0` and `100.0`, using the nearest-rank method. Returns `None`
+    /// if the heatmap is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::Heatmap;
+    ///
+    /// let heatmap = Heatmap::from_fn(11, 1, |x, _| x as f64 / 10.0);
+    /// assert_eq!(Some(0.9), heatmap.percentile(90.0));
+    /// ```
+    pub fn percentile(&self, percentile: f64) -> Option<T> {
+        if self.as_raw().is_empty() {
+            return None;
+        }
+
+        let mut heat = self.as_raw().to_vec();
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * (heat.len() - 1) as f64).round();
+        let (_, heat, _) =
+            heat.select_nth_unstable_by(rank as usize, |a, b| a.to_f64().total_cmp(&b.to_f64()));
+
+        Some(*heat)
+    }
+
+    /// Counts the pixels of the heatmap in the given number of buckets, which
+    /// evenly split the range between its coldest and hottest pixels. The
+    /// hottest pixels fall into the last bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of buckets is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libgeometrize::images::Heatmap;
+    ///
+    /// let heatmap = Heatmap::from_fn(4, 1, |x, _| [0, 2, 3, 4][x as usize] as u64);
+    /// let histogram = heatmap.histogram(2);
+    ///
+    /// assert_eq!(vec![1, 3], histogram.counts);
+    /// assert_eq!((2.0, 4.0), histogram.bucket_range(1));
+    /// ```
+    pub fn histogram(&self, buckets: usize) -> Histogram {
+        assert!(buckets > 0, "a histogram needs at least one bucket");
+
+        let min = self.min().map_or(0.0, |(_, heat)| heat.to_f64());
+        let max = self.max().map_or(0.0, |(_, heat)| heat.to_f64());
+
+        let mut counts = vec![0; buckets];
+        for heat in self.as_raw() {
+            let position = if max > min {
+                (heat.to_f64() - min) / (max - min) * buckets as f64
+            } else {
+                0.0
+            };
+            counts[(position as usize).min(buckets - 1)] += 1;
+        }
+
+        Histogram { min, max, counts }
+    }
+
+    /// Returns the first pixel of the heatmap in reading order which is
+    /// strictly colder or hotter, according to `ordering`, than all the others.
+    fn extremum(&self, ordering: Ordering) -> Option<((u32, u32), T)> {
+        let (idx, heat) = self.as_raw().iter().enumerate().reduce(|best, current| {
+            if current.1.partial_cmp(best.1) == Some(ordering) {
+                current
+            } else {
+                best
+            }
+        })?;
+
+        let idx = idx as u32;
+        Some(((idx % self.width(), idx / self.width()), *heat))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_computes_the_moments_of_a_heatmap() {
+        let heatmap = Heatmap::from_fn(4, 1, |x, _| [2, 4, 4, 6][x as usize] as u8);
+
+        assert_eq!(16.0, heatmap.sum());
+        assert_eq!(4.0, heatmap.mean());
+        assert_eq!(2.0, heatmap.variance());
+    }
+
+    #[test]
+    fn it_locates_the_extrema_of_a_heatmap() {
+        let heatmap: Heatmap = Heatmap::from_fn(3, 3, |x, y| {
+            [5, 1, 9, 9, 3, 1, 7, 2, 8][(3 * y + x) as usize]
+        });
+
+        assert_eq!(Some(((1, 0), 1)), heatmap.min());
+        assert_eq!(Some(((2, 0), 9)), heatmap.max());
+    }
+
+    #[test]
+    fn it_computes_the_percentiles_of_a_heatmap() {
+        let heatmap: Heatmap = Heatmap::from_fn(5, 1, |x, _| [7, 100, 0, 3, 1][x as usize]);
+
+        assert_eq!(Some(0), heatmap.percentile(0.0));
+        assert_eq!(Some(3), heatmap.percentile(50.0));
+        assert_eq!(Some(7), heatmap.percentile(75.0));
+        assert_eq!(Some(100), heatmap.percentile(100.0));
+    }
+
+    #[test]
+    fn it_computes_the_statistics_of_an_empty_heatmap() {
+        let heatmap = Heatmap::<f32>::new(0, 0);
+
+        assert_eq!(
+            (0.0, 0.0, 0.0),
+            (heatmap.sum(), heatmap.mean(), heatmap.variance())
+        );
+        assert_eq!(None, heatmap.max());
+        assert_eq!(None, heatmap.percentile(50.0));
+        assert_eq!(vec![0, 0], heatmap.histogram(2).counts);
+    }
+
+    #[test]
+    fn it_builds_the_histogram_of_a_heatmap() {
+        let heatmap = Heatmap::from_fn(10, 1, |x, _| x as f64);
+        let histogram = heatmap.histogram(3);
+
+        assert_eq!(vec![3, 3, 4], histogram.counts);
+        assert_eq!((0.0, 3.0), histogram.bucket_range(0));
+
+        let cold = Heatmap::<u16>::new(2, 2).histogram(4);
+        assert_eq!(vec![4, 0, 0, 0], cold.counts);
+    }
+}
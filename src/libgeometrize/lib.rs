@@ -1,2 +1,4 @@
+pub mod formats;
 pub mod images;
 pub mod math;
+pub mod optimizer;
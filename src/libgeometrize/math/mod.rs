@@ -1,6 +1,8 @@
 mod point;
+mod scanline;
 pub mod shapes;
 mod vector;
 
 pub use point::Point;
+pub use scanline::{AntialiasedScanline, Coverage, Rasterizer, Scanline};
 pub use vector::Vector;
@@ -0,0 +1,181 @@
+/// Defines a rasterized horizontal line of pixels along with the fraction of
+/// each of these pixels covered by a shape.
+pub trait Coverage {
+    /// Returns the row of the line.
+    fn y(&self) -> u32;
+
+    /// Returns the first column of the line.
+    fn x1(&self) -> u32;
+
+    /// Returns the last column of the line, which is inclusive.
+    fn x2(&self) -> u32;
+
+    /// Returns the fraction, between `0.0` and `1.0`, of the pixel of column
+    /// `x` covered by the shape.
+    fn coverage(&self, x: u32) -> f64;
+}
+
+/// Defines a horizontal line of pixels covered by a shape. The line lies on the
+/// row `y` and goes from the column `x1` to the column `x2`, both inclusive.
+///
+/// Scanlines are always clipped to the bounds of the image they've been
+/// rasterized for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scanline {
+    pub y: u32,
+    pub x1: u32,
+    pub x2: u32,
+}
+
+impl Scanline {
+    /// Instanciates a new scanline on the row `y` going from `x1` to `x2`.
+    pub const fn new(y: u32, x1: u32, x2: u32) -> Self {
+        Self { y, x1, x2 }
+    }
+}
+
+impl Coverage for Scanline {
+    #[inline]
+    fn y(&self) -> u32 {
+        self.y
+    }
+
+    #[inline]
+    fn x1(&self) -> u32 {
+        self.x1
+    }
+
+    #[inline]
+    fn x2(&self) -> u32 {
+        self.x2
+    }
+
+    /// A hard-edged scanline is always fully covering its pixels.
+    #[inline]
+    fn coverage(&self, _x: u32) -> f64 {
+        1.0
+    }
+}
+
+/// Defines a horizontal line of pixels partially covered by a shape. The line
+/// lies on the row `y` and starts at the column `x1`, each of its pixels having
+/// its own coverage.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AntialiasedScanline {
+    pub y: u32,
+    pub x1: u32,
+    coverage: Vec<f64>,
+}
+
+impl AntialiasedScanline {
+    /// Instanciates a new antialiased scanline on the row `y` starting at `x1`
+    /// with the coverage of each of its pixels. The coverage must not be empty.
+    pub fn new(y: u32, x1: u32, coverage: Vec<f64>) -> Self {
+        debug_assert!(!coverage.is_empty());
+
+        Self { y, x1, coverage }
+    }
+}
+
+impl Coverage for AntialiasedScanline {
+    #[inline]
+    fn y(&self) -> u32 {
+        self.y
+    }
+
+    #[inline]
+    fn x1(&self) -> u32 {
+        self.x1
+    }
+
+    #[inline]
+    fn x2(&self) -> u32 {
+        self.x1 + self.coverage.len() as u32 - 1
+    }
+
+    #[inline]
+    fn coverage(&self, x: u32) -> f64 {
+        self.coverage[(x - self.x1) as usize]
+    }
+}
+
+/// Defines the rasterization method used to turn a shape into pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rasterizer {
+    /// Pixels are either fully covered or not covered at all, depending on
+    /// whether their center lies inside the shape or not.
+    #[default]
+    Aliased,
+
+    /// Pixels are covered by the fraction of their area lying inside the shape,
+    /// which smooths the edges of the shape.
+    Antialiased,
+}
+
+/// Adds `weight` times the horizontal coverage of the span going from `start`
+/// to `end` to each pixel of a row. The span must lie inside the row.
+pub(crate) fn accumulate_span(row: &mut [f64], start: f64, end: f64, weight: f64) {
+    let first = start.floor() as usize;
+    let last = end.floor() as usize;
+
+    if first == last {
+        row[first] += (end - start) * weight;
+        return;
+    }
+
+    row[first] += (first as f64 + 1.0 - start) * weight;
+    for px in row.iter_mut().take(last).skip(first + 1) {
+        *px += weight;
+    }
+    if last < row.len() {
+        row[last] += (end - last as f64) * weight;
+    }
+}
+
+/// Turns the accumulated coverage of a row into antialiased scanlines, one for
+/// each run of covered pixels, and resets the row.
+pub(crate) fn drain_row(row: &mut [f64], y: u32, lines: &mut Vec<AntialiasedScanline>) {
+    let mut run: Option<(u32, Vec<f64>)> = None;
+
+    for (x, px) in row.iter_mut().enumerate() {
+        let coverage = std::mem::take(px).min(1.0);
+
+        if coverage > f64::EPSILON {
+            run.get_or_insert_with(|| (x as u32, Vec::new()))
+                .1
+                .push(coverage);
+        } else if let Some((x1, coverage)) = run.take() {
+            lines.push(AntialiasedScanline::new(y, x1, coverage));
+        }
+    }
+
+    if let Some((x1, coverage)) = run {
+        lines.push(AntialiasedScanline::new(y, x1, coverage));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_accumulates_the_coverage_of_a_span() {
+        let mut row = vec![0.0; 5];
+        accumulate_span(&mut row, 0.5, 3.25, 1.0);
+
+        assert_eq!(vec![0.5, 1.0, 1.0, 0.25, 0.0], row);
+    }
+
+    #[test]
+    fn it_drains_a_row_into_antialiased_scanlines() {
+        let mut row = vec![0.0, 0.5, 1.0, 0.0, 0.25];
+        let mut lines = Vec::new();
+        drain_row(&mut row, 3, &mut lines);
+
+        assert_eq!(2, lines.len());
+        assert_eq!((1, 2), (lines[0].x1(), lines[0].x2()));
+        assert_eq!(0.5, lines[0].coverage(1));
+        assert_eq!((4, 4), (lines[1].x1(), lines[1].x2()));
+        assert!(row.iter().all(|px| *px == 0.0));
+    }
+}
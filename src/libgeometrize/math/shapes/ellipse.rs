@@ -1,4 +1,6 @@
-use crate::math::Point;
+use super::{angle_jitter, clamp_point, jitter, mutate_until_valid, random_point, RANDOM_EXTENT};
+use crate::math::{Point, Vector};
+use rand::{Rng, RngCore};
 
 /// Defines an ellipse by using its cartesian representation:
 /// $\left ( \frac{x - u}{a} \right ) ^ 2 + \left ( \frac{y - v}{b} \right ) ^ 2 = 1$
@@ -12,9 +14,11 @@ use crate::math::Point;
 ///
 /// ```
 /// use libgeometrize::math::shapes::Ellipse;
+/// use rand::{rngs::StdRng, SeedableRng};
 ///
-/// // Generating a random non-rotated ellipse
-/// let ellipse = Ellipse::random();
+/// // Generating a random non-rotated ellipse on a 256x256 canvas
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let ellipse = Ellipse::random(&mut rng, 256, 256);
 ///
 /// // Generating a rotated ellipse with its parameters
 /// let ellipse = Ellipse::new()
@@ -28,6 +32,7 @@ use crate::math::Point;
 ///
 /// [Shape]: ./Shape.trait.html
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ellipse {
     center: Point,
     a: f64,
@@ -36,39 +41,85 @@ pub struct Ellipse {
 }
 
 impl Ellipse {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> EllipseBuilder {
         EllipseBuilder::default()
     }
 
-    /// Instanciates a new random non-rotated ellipse.
-    pub fn random() -> Self {
-        // TODO: generates the random ellipse thanks to a RNG
+    /// Instanciates a new random non-rotated ellipse centered inside a canvas
+    /// of the given dimensions, thanks to a user-supplied RNG.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, width: u32, height: u32) -> Self {
+        let center = random_point(rng, width, height);
+        Self::random_at(rng, center, width, height)
+    }
+
+    /// Instanciates a new random non-rotated ellipse centered on the given
+    /// point of a canvas of the given dimensions, thanks to a user-supplied RNG.
+    pub fn random_at<R: Rng + ?Sized>(rng: &mut R, center: Point, width: u32, height: u32) -> Self {
         Self {
-            center: Point::zero(),
-            a: 1.0,
-            b: 1.0,
+            center: clamp_point(center, width, height),
+            a: rng.gen_range(1.0..=RANDOM_EXTENT),
+            b: rng.gen_range(1.0..=RANDOM_EXTENT),
             angle: None,
         }
     }
 
-    /// Instanciates a new random rotated ellipse.
-    pub fn random_rotated() -> Self {
-        // TODO: generates a random rotated ellipse thanks to a RNG
+    /// Instanciates a new random rotated ellipse centered inside a canvas
+    /// of the given dimensions, thanks to a user-supplied RNG.
+    pub fn random_rotated<R: Rng + ?Sized>(rng: &mut R, width: u32, height: u32) -> Self {
+        let center = random_point(rng, width, height);
+        Self::random_rotated_at(rng, center, width, height)
+    }
+
+    /// Instanciates a new random rotated ellipse centered on the given point of
+    /// a canvas of the given dimensions, thanks to a user-supplied RNG.
+    pub fn random_rotated_at<R: Rng + ?Sized>(
+        rng: &mut R,
+        center: Point,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let ellipse = Self::random_at(rng, center, width, height);
+
         Self {
-            center: Point::zero(),
-            a: 1.0,
-            b: 1.0,
-            angle: Some(0.0),
+            angle: Some(rng.gen_range(0.0..std::f64::consts::PI)),
+            ..ellipse
         }
     }
 
+    /// Returns the center of the current ellipse.
+    #[inline]
+    pub const fn center(&self) -> Point {
+        self.center
+    }
+
+    /// Returns the half-axis of the current ellipse along the abscissas, before
+    /// the ellipse is rotated.
+    #[inline]
+    pub const fn a(&self) -> f64 {
+        self.a
+    }
+
+    /// Returns the half-axis of the current ellipse along the ordinates, before
+    /// the ellipse is rotated.
+    #[inline]
+    pub const fn b(&self) -> f64 {
+        self.b
+    }
+
+    /// Returns the rotation angle of the current ellipse, if it's rotated.
+    #[inline]
+    pub const fn angle(&self) -> Option<f64> {
+        self.angle
+    }
+
     /// Indicates if the current ellipse is a circle by checking that its half-heights
     /// `a` and `b` are equals.
     pub fn is_circle(&self) -> bool {
         // Since `a` and `b` are floating point numbers, we cannot be sure that they are
         // strictly equals. We'll consider them equals if their difference is strictly
         // lesser than `f64::EPSILON`
-        self.a - self.b < f64::EPSILON
+        (self.a - self.b).abs() < f64::EPSILON
     }
 
     /// Indicates if the current elippse is rotated or not.
@@ -78,8 +129,55 @@ impl Ellipse {
 }
 
 impl super::Shape for Ellipse {
-    fn mutate(&mut self) {
-        //
+    /// Mutates either the center, one of the half-axes or, for a rotated
+    /// ellipse, the rotation angle.
+    fn mutate(&mut self, rng: &mut dyn RngCore, width: u32, height: u32) {
+        let parameters = if self.is_rotated() { 4 } else { 3 };
+
+        mutate_until_valid(self, |ellipse| match rng.gen_range(0..parameters) {
+            0 => {
+                let offset = Vector::new(jitter(rng), jitter(rng));
+                ellipse.center = clamp_point(ellipse.center + offset, width, height);
+            }
+            1 => ellipse.a = (ellipse.a + jitter(rng)).clamp(1.0, width.max(1) as f64),
+            2 => ellipse.b = (ellipse.b + jitter(rng)).clamp(1.0, height.max(1) as f64),
+            _ => ellipse.angle = ellipse.angle.map(|angle| angle + angle_jitter(rng)),
+        });
+    }
+
+    fn spans(&self, y: f64) -> Vec<(f64, f64)> {
+        // A degenerate ellipse covers no area, and its equation cannot be solved
+        if !(self.a.is_finite() && self.a > 0.0 && self.b.is_finite() && self.b > 0.0) {
+            return Vec::new();
+        }
+
+        // Points of the rotated ellipse are satisfying a quadratic equation in `x`
+        // once `y` is fixed, whose roots are the boundaries of the span.
+        let (sin, cos) = self.angle.unwrap_or(0.0).sin_cos();
+        let (a2, b2) = (self.a * self.a, self.b * self.b);
+        let dy = y - self.center.y;
+
+        let qa = cos * cos / a2 + sin * sin / b2;
+        let qb = 2.0 * dy * sin * cos * (1.0 / a2 - 1.0 / b2);
+        let qc = dy * dy * (sin * sin / a2 + cos * cos / b2) - 1.0;
+
+        let discriminant = qb * qb - 4.0 * qa * qc;
+        if discriminant.is_nan() || discriminant < 0.0 {
+            return Vec::new();
+        }
+
+        let root = discriminant.sqrt();
+        vec![(
+            self.center.x + (-qb - root) / (2.0 * qa),
+            self.center.x + (-qb + root) / (2.0 * qa),
+        )]
+    }
+
+    fn vertical_bounds(&self) -> (f64, f64) {
+        let (sin, cos) = self.angle.unwrap_or(0.0).sin_cos();
+        let extent = (self.a * self.a * sin * sin + self.b * self.b * cos * cos).sqrt();
+
+        (self.center.y - extent, self.center.y + extent)
     }
 }
 
@@ -131,6 +229,8 @@ impl EllipseBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::shapes::tests::{assert_area, coverage, rng};
+    use crate::math::shapes::Shape;
 
     #[test]
     fn it_indicates_if_an_ellipse_is_a_circle() {
@@ -144,6 +244,24 @@ mod tests {
         assert!(circle.is_circle())
     }
 
+    #[test]
+    fn it_indicates_if_an_ellipse_is_not_a_circle() {
+        let wide = Ellipse {
+            center: Point::zero(),
+            a: 2.0,
+            b: 1.0,
+            angle: None,
+        };
+        let tall = Ellipse {
+            a: 1.0,
+            b: 2.0,
+            ..wide
+        };
+
+        assert!(!wide.is_circle());
+        assert!(!tall.is_circle());
+    }
+
     #[test]
     fn it_indicates_if_an_ellipse_is_rotated() {
         let ellipse = Ellipse {
@@ -155,4 +273,93 @@ mod tests {
 
         assert!(ellipse.is_rotated());
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_serializes_an_ellipse() {
+        let ellipse = Ellipse::new()
+            .u(1.0)
+            .v(2.0)
+            .a(3.0)
+            .b(4.0)
+            .angle(0.5)
+            .build();
+
+        let json = serde_json::to_string(&ellipse).unwrap();
+        let ellipse: Ellipse = serde_json::from_str(&json).unwrap();
+
+        assert_eq!((1.0, 2.0), (ellipse.center.x, ellipse.center.y));
+        assert_eq!((3.0, 4.0, Some(0.5)), (ellipse.a, ellipse.b, ellipse.angle));
+    }
+
+    #[test]
+    fn it_generates_reproducible_random_ellipses_on_the_canvas() {
+        let a = Ellipse::random_rotated(&mut rng(), 64, 32);
+        let b = Ellipse::random_rotated(&mut rng(), 64, 32);
+
+        assert_eq!(a.center.x, b.center.x);
+        assert_eq!(a.angle, b.angle);
+        assert!(a.center.x >= 0.0 && a.center.x < 64.0);
+        assert!(a.center.y >= 0.0 && a.center.y < 32.0);
+        assert!(a.a >= 1.0 && a.b >= 1.0);
+    }
+
+    #[test]
+    fn it_mutates_an_ellipse_without_leaving_the_canvas() {
+        let mut rng = rng();
+        let mut ellipse = Ellipse::random(&mut rng, 64, 32);
+        for _ in 0..1000 {
+            ellipse.mutate(&mut rng, 64, 32);
+
+            assert!(!ellipse.is_rotated());
+            assert!(ellipse.center.x >= 0.0 && ellipse.center.x <= 64.0);
+            assert!(ellipse.center.y >= 0.0 && ellipse.center.y <= 32.0);
+            assert!(ellipse.a >= 1.0 && ellipse.a <= 64.0);
+            assert!(ellipse.b >= 1.0 && ellipse.b <= 32.0);
+        }
+    }
+
+    #[test]
+    fn it_rasterizes_a_rotated_ellipse() {
+        let ellipse = Ellipse::new()
+            .u(50.0)
+            .v(50.0)
+            .a(30.0)
+            .b(12.0)
+            .angle(std::f64::consts::FRAC_PI_6)
+            .build();
+
+        assert_area(&ellipse, std::f64::consts::PI * 30.0 * 12.0);
+    }
+
+    #[test]
+    fn it_clips_the_rasterization_of_an_ellipse() {
+        let ellipse = Ellipse::new().u(0.0).v(0.0).a(20.0).b(20.0).build();
+        let area = std::f64::consts::PI * 20.0 * 20.0 / 4.0;
+
+        assert!(ellipse
+            .rasterize(100, 100)
+            .iter()
+            .all(|l| l.x1 <= l.x2 && l.x2 < 100 && l.y < 100));
+        assert!((coverage(&ellipse) as f64 - area).abs() / area < 0.05);
+    }
+
+    #[test]
+    fn it_rasterizes_nothing_for_a_degenerate_ellipse() {
+        let flat = Ellipse::new().u(10.0).v(10.3).a(5.0).b(0.0).build();
+        let empty = Ellipse::new().build();
+        let infinite = Ellipse::new()
+            .u(10.0)
+            .v(10.0)
+            .a(f64::INFINITY)
+            .b(5.0)
+            .build();
+
+        assert!(flat.rasterize(100, 100).is_empty());
+        assert!(empty.rasterize(100, 100).is_empty());
+        assert!(infinite.rasterize(100, 100).is_empty());
+        assert!(flat.rasterize_antialiased(100, 100).is_empty());
+        assert!(empty.rasterize_antialiased(100, 100).is_empty());
+        assert!(infinite.rasterize_antialiased(100, 100).is_empty());
+    }
 }
@@ -0,0 +1,205 @@
+use super::{Ellipse, Polygon, Rectangle, Shape, Triangle};
+use crate::math::Point;
+use rand::{Rng, RngCore};
+
+/// Defines the kinds of shapes which can be randomly generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShapeKind {
+    Ellipse,
+    RotatedEllipse,
+    Rectangle,
+    RotatedRectangle,
+    Triangle,
+    /// A convex polygon of the given order.
+    Polygon(usize),
+}
+
+impl ShapeKind {
+    /// Instanciates a new random shape of the current kind lying inside a
+    /// canvas of the given dimensions, thanks to a user-supplied RNG.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current kind is a polygon of order lesser than 3.
+    pub fn random<R: Rng + ?Sized>(&self, rng: &mut R, width: u32, height: u32) -> AnyShape {
+        match *self {
+            ShapeKind::Ellipse => Ellipse::random(rng, width, height).into(),
+            ShapeKind::RotatedEllipse => Ellipse::random_rotated(rng, width, height).into(),
+            ShapeKind::Rectangle => Rectangle::random(rng, width, height).into(),
+            ShapeKind::RotatedRectangle => Rectangle::random_rotated(rng, width, height).into(),
+            ShapeKind::Triangle => Triangle::random(rng, width, height).into(),
+            ShapeKind::Polygon(order) => Polygon::random(rng, width, height, order).into(),
+        }
+    }
+
+    /// Instanciates a new random shape of the current kind centered on the
+    /// given point of a canvas of the given dimensions, thanks to a
+    /// user-supplied RNG.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current kind is a polygon of order lesser than 3.
+    pub fn random_at<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        center: Point,
+        width: u32,
+        height: u32,
+    ) -> AnyShape {
+        match *self {
+            ShapeKind::Ellipse => Ellipse::random_at(rng, center, width, height).into(),
+            ShapeKind::RotatedEllipse => {
+                Ellipse::random_rotated_at(rng, center, width, height).into()
+            }
+            ShapeKind::Rectangle => Rectangle::random_at(rng, center, width, height).into(),
+            ShapeKind::RotatedRectangle => {
+                Rectangle::random_rotated_at(rng, center, width, height).into()
+            }
+            ShapeKind::Triangle => Triangle::random_at(rng, center, width, height).into(),
+            ShapeKind::Polygon(order) => {
+                Polygon::random_at(rng, center, width, height, order).into()
+            }
+        }
+    }
+}
+
+/// Defines any of the shapes provided by this module, which allows to handle
+/// shapes of different kinds together.
+///
+/// # Example
+///
+/// ```
+/// use libgeometrize::math::shapes::{AnyShape, ShapeKind};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let shapes: Vec<AnyShape> = [ShapeKind::RotatedEllipse, ShapeKind::Triangle]
+///     .iter()
+///     .map(|kind| kind.random(&mut rng, 256, 256))
+///     .collect();
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnyShape {
+    Ellipse(Ellipse),
+    Rectangle(Rectangle),
+    Triangle(Triangle),
+    Polygon(Polygon),
+}
+
+impl Shape for AnyShape {
+    fn mutate(&mut self, rng: &mut dyn RngCore, width: u32, height: u32) {
+        match self {
+            AnyShape::Ellipse(shape) => shape.mutate(rng, width, height),
+            AnyShape::Rectangle(shape) => shape.mutate(rng, width, height),
+            AnyShape::Triangle(shape) => shape.mutate(rng, width, height),
+            AnyShape::Polygon(shape) => shape.mutate(rng, width, height),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match self {
+            AnyShape::Ellipse(shape) => shape.is_valid(),
+            AnyShape::Rectangle(shape) => shape.is_valid(),
+            AnyShape::Triangle(shape) => shape.is_valid(),
+            AnyShape::Polygon(shape) => Shape::is_valid(shape),
+        }
+    }
+
+    fn spans(&self, y: f64) -> Vec<(f64, f64)> {
+        match self {
+            AnyShape::Ellipse(shape) => shape.spans(y),
+            AnyShape::Rectangle(shape) => shape.spans(y),
+            AnyShape::Triangle(shape) => shape.spans(y),
+            AnyShape::Polygon(shape) => shape.spans(y),
+        }
+    }
+
+    fn vertical_bounds(&self) -> (f64, f64) {
+        match self {
+            AnyShape::Ellipse(shape) => shape.vertical_bounds(),
+            AnyShape::Rectangle(shape) => shape.vertical_bounds(),
+            AnyShape::Triangle(shape) => shape.vertical_bounds(),
+            AnyShape::Polygon(shape) => shape.vertical_bounds(),
+        }
+    }
+}
+
+impl From<Ellipse> for AnyShape {
+    fn from(shape: Ellipse) -> Self {
+        AnyShape::Ellipse(shape)
+    }
+}
+
+impl From<Rectangle> for AnyShape {
+    fn from(shape: Rectangle) -> Self {
+        AnyShape::Rectangle(shape)
+    }
+}
+
+impl From<Triangle> for AnyShape {
+    fn from(shape: Triangle) -> Self {
+        AnyShape::Triangle(shape)
+    }
+}
+
+impl From<Polygon> for AnyShape {
+    fn from(shape: Polygon) -> Self {
+        AnyShape::Polygon(shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::shapes::tests::rng;
+
+    #[test]
+    fn it_generates_shapes_of_the_requested_kind() {
+        let mut rng = rng();
+
+        match ShapeKind::RotatedEllipse.random(&mut rng, 64, 64) {
+            AnyShape::Ellipse(ellipse) => assert!(ellipse.is_rotated()),
+            shape => panic!("unexpected shape {:?}", shape),
+        }
+        match ShapeKind::Rectangle.random(&mut rng, 64, 64) {
+            AnyShape::Rectangle(rect) => assert!(!rect.is_rotated()),
+            shape => panic!("unexpected shape {:?}", shape),
+        }
+        match ShapeKind::Polygon(6).random(&mut rng, 64, 64) {
+            AnyShape::Polygon(polygon) => assert_eq!(6, polygon.order()),
+            shape => panic!("unexpected shape {:?}", shape),
+        }
+    }
+
+    #[test]
+    fn it_delegates_the_shape_behavior() {
+        let mut rng = rng();
+        let triangle = Triangle::random(&mut rng, 64, 64);
+        let shape = AnyShape::from(triangle);
+
+        assert!(shape.is_valid());
+        assert_eq!(triangle.rasterize(64, 64), shape.rasterize(64, 64));
+    }
+
+    #[test]
+    fn it_generates_shapes_on_an_empty_canvas() {
+        let mut rng = rng();
+        let kinds = [
+            ShapeKind::Ellipse,
+            ShapeKind::RotatedEllipse,
+            ShapeKind::Rectangle,
+            ShapeKind::RotatedRectangle,
+            ShapeKind::Triangle,
+            ShapeKind::Polygon(5),
+        ];
+
+        for kind in kinds.iter() {
+            let mut shape = kind.random(&mut rng, 0, 0);
+            shape.mutate(&mut rng, 0, 0);
+
+            assert!(shape.rasterize(0, 0).is_empty());
+        }
+    }
+}
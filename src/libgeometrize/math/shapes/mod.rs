@@ -1,20 +1,346 @@
+use crate::math::scanline::{accumulate_span, drain_row};
+use crate::math::{AntialiasedScanline, Point, Scanline};
+use rand::{Rng, RngCore};
+
 mod ellipse;
+mod kind;
 mod polygon;
 mod rectangle;
 mod triangle;
 
 /// Defines the common behavior of all mathematical shapes.
 pub trait Shape {
-    fn mutate(&mut self);
+    /// Randomly perturbs a single parameter of the current shape thanks to
+    /// a user-supplied RNG. The mutated shape stays on a canvas of the given
+    /// dimensions and keeps being valid.
+    ///
+    /// The RNG is taken as a trait object so that shapes can themselves be
+    /// handled as `dyn Shape`.
+    fn mutate(&mut self, rng: &mut dyn RngCore, width: u32, height: u32);
 
     /// Indicates if the current shape instance is valid or not by a
     /// user-defined constraint. By default, a shape is always valid.
     fn is_valid(&self) -> bool {
         true
     }
+
+    /// Returns the horizontal spans covered by the shape on the horizontal line
+    /// of ordinate `y`. Each span is given by its starting and ending abscissas.
+    fn spans(&self, y: f64) -> Vec<(f64, f64)>;
+
+    /// Returns the smallest and the greatest ordinates reached by the shape.
+    fn vertical_bounds(&self) -> (f64, f64);
+
+    /// Rasterizes the shape into the scanlines covering an image of the given
+    /// dimensions. A pixel is covered by the shape if its center is inside it.
+    fn rasterize(&self, width: u32, height: u32) -> Vec<Scanline> {
+        let (top, bottom) = self.vertical_bounds();
+        let first_row = top.floor().max(0.0) as u32;
+        let last_row = bottom.ceil().min(height as f64) as u32;
+
+        let mut lines = Vec::new();
+        for y in first_row..last_row {
+            for (start, end) in self.spans(y as f64 + 0.5) {
+                if !(start.is_finite() && end.is_finite()) {
+                    continue;
+                }
+
+                let x1 = (start - 0.5).ceil().max(0.0);
+                let x2 = (end - 0.5).floor().min(width as f64 - 1.0);
+
+                if x1 <= x2 {
+                    lines.push(Scanline::new(y, x1 as u32, x2 as u32));
+                }
+            }
+        }
+
+        lines
+    }
+
+    /// Rasterizes the shape into antialiased scanlines covering an image of the
+    /// given dimensions. The coverage of each pixel is computed exactly along
+    /// the horizontal axis and by supersampling along the vertical axis.
+    fn rasterize_antialiased(&self, width: u32, height: u32) -> Vec<AntialiasedScanline> {
+        let (top, bottom) = self.vertical_bounds();
+        let first_row = top.floor().max(0.0) as u32;
+        let last_row = bottom.ceil().min(height as f64) as u32;
+        let weight = 1.0 / ANTIALIASING_SAMPLES as f64;
+
+        let mut lines = Vec::new();
+        let mut row = vec![0.0; width as usize];
+        for y in first_row..last_row {
+            for sample in 0..ANTIALIASING_SAMPLES {
+                let sample_y = y as f64 + (sample as f64 + 0.5) * weight;
+
+                for (start, end) in self.spans(sample_y) {
+                    if !(start.is_finite() && end.is_finite()) {
+                        continue;
+                    }
+
+                    let (start, end) = (start.max(0.0), end.min(width as f64));
+                    if start < end {
+                        accumulate_span(&mut row, start, end, weight);
+                    }
+                }
+            }
+
+            drain_row(&mut row, y, &mut lines);
+        }
+
+        lines
+    }
+}
+
+/// The maximal extent, in pixels, of a randomly generated shape before any
+/// mutation happens. This is the value used by the original Geometrize.
+const RANDOM_EXTENT: f64 = 32.0;
+
+/// The number of horizontal lines sampled inside each row of pixels when a
+/// shape is rasterized with antialiasing.
+const ANTIALIASING_SAMPLES: u32 = 4;
+
+/// The maximal offset, in pixels, applied to a parameter of a shape when it's
+/// mutated.
+const MUTATION_EXTENT: f64 = 16.0;
+
+/// The maximal rotation, in degrees, applied to a shape when it's mutated.
+const MUTATION_ANGLE: f64 = 32.0;
+
+/// The maximal number of attempts made to find a valid mutation of a shape
+/// before giving up and leaving it untouched.
+const MAX_MUTATION_ATTEMPTS: usize = 64;
+
+/// Returns a random offset to apply to a parameter during a mutation.
+fn jitter<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    rng.gen_range(-MUTATION_EXTENT..=MUTATION_EXTENT)
+}
+
+/// Returns a random rotation, in radians, to apply during a mutation.
+fn angle_jitter<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    rng.gen_range(-MUTATION_ANGLE..=MUTATION_ANGLE).to_radians()
+}
+
+/// Applies a mutation to a copy of `shape` until the copy is valid, then
+/// replaces `shape` by it. If no valid mutation is found after
+/// [`MAX_MUTATION_ATTEMPTS`] attempts, the shape is left untouched.
+fn mutate_until_valid<S, F>(shape: &mut S, mut mutation: F)
+where
+    S: Shape + Clone,
+    F: FnMut(&mut S),
+{
+    for _ in 0..MAX_MUTATION_ATTEMPTS {
+        let mut candidate = shape.clone();
+        mutation(&mut candidate);
+
+        if candidate.is_valid() {
+            *shape = candidate;
+            return;
+        }
+    }
+}
+
+/// Clamps a point so it stays on a canvas of the given dimensions.
+fn clamp_point(point: Point, width: u32, height: u32) -> Point {
+    Point::new(
+        point.x.clamp(0.0, width as f64),
+        point.y.clamp(0.0, height as f64),
+    )
+}
+
+/// Computes the spans covered by a polygon on the horizontal line of ordinate
+/// `y` by using the even-odd rule, which also handles non-convex polygons.
+/// Edges crossing the line at a non-finite abscissa are ignored.
+fn polygon_spans(vertices: &[Point], y: f64) -> Vec<(f64, f64)> {
+    let mut intersections = Vec::new();
+    for (idx, p) in vertices.iter().enumerate() {
+        let q = vertices[(idx + 1) % vertices.len()];
+
+        if (p.y <= y && y < q.y) || (q.y <= y && y < p.y) {
+            let x = p.x + (y - p.y) * (q.x - p.x) / (q.y - p.y);
+            if x.is_finite() {
+                intersections.push(x);
+            }
+        }
+    }
+    intersections.sort_by(f64::total_cmp);
+
+    intersections
+        .chunks_exact(2)
+        .map(|span| (span[0], span[1]))
+        .collect()
+}
+
+/// Returns the smallest and the greatest ordinates of a set of vertices.
+fn polygon_vertical_bounds(vertices: &[Point]) -> (f64, f64) {
+    vertices
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(top, bottom), p| {
+            (top.min(p.y), bottom.max(p.y))
+        })
+}
+
+/// Picks a random point on a canvas of the given dimensions. Along an empty
+/// dimension of the canvas, the point lies on its edge.
+fn random_point<R: Rng + ?Sized>(rng: &mut R, width: u32, height: u32) -> Point {
+    Point::new(
+        random_coordinate(rng, width),
+        random_coordinate(rng, height),
+    )
+}
+
+/// Picks a random coordinate between `0` and `extent`, or `0` if the extent is
+/// empty.
+fn random_coordinate<R: Rng + ?Sized>(rng: &mut R, extent: u32) -> f64 {
+    if extent == 0 {
+        return 0.0;
+    }
+
+    rng.gen_range(0.0..extent as f64)
+}
+
+/// Picks a random circle around `center` that fits entirely inside a canvas of
+/// the given dimensions, the center being moved towards the inside of the
+/// canvas when needed. The circle radius is at most half of [`RANDOM_EXTENT`].
+fn random_circle<R: Rng + ?Sized>(
+    rng: &mut R,
+    center: Point,
+    width: u32,
+    height: u32,
+) -> (Point, f64) {
+    let (width, height) = (width as f64, height as f64);
+    let radius = rng
+        .gen_range(0.5..=RANDOM_EXTENT / 2.0)
+        .min(width / 2.0)
+        .min(height / 2.0);
+
+    let center = Point::new(
+        center.x.clamp(radius, width - radius),
+        center.y.clamp(radius, height - radius),
+    );
+
+    (center, radius)
 }
 
 pub use ellipse::Ellipse;
+pub use kind::{AnyShape, ShapeKind};
 pub use polygon::Polygon;
 pub use rectangle::Rectangle;
 pub use triangle::Triangle;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Coverage;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Returns the seeded RNG shared by the tests of the shapes.
+    pub(super) fn rng() -> StdRng {
+        StdRng::seed_from_u64(42)
+    }
+
+    /// Asserts that all the points lie on a canvas of the given dimensions.
+    pub(super) fn assert_on_canvas(points: &[Point], width: u32, height: u32) {
+        assert!(points
+            .iter()
+            .all(|p| { p.x >= 0.0 && p.x <= width as f64 && p.y >= 0.0 && p.y <= height as f64 }));
+    }
+
+    /// Returns the number of pixels covered by a shape on a 100x100 image.
+    pub(super) fn coverage<S: Shape>(shape: &S) -> u32 {
+        shape
+            .rasterize(100, 100)
+            .iter()
+            .map(|l| l.x2 - l.x1 + 1)
+            .sum()
+    }
+
+    /// Returns the total antialiased coverage of a shape on a 100x100 image.
+    pub(super) fn antialiased_coverage<S: Shape>(shape: &S) -> f64 {
+        shape
+            .rasterize_antialiased(100, 100)
+            .iter()
+            .flat_map(|l| (l.x1()..=l.x2()).map(move |x| l.coverage(x)))
+            .sum()
+    }
+
+    /// Asserts that both rasterizations of a shape on a 100x100 image cover
+    /// the given area, the antialiased one being more accurate.
+    pub(super) fn assert_area<S: Shape>(shape: &S, area: f64) {
+        assert!((coverage(shape) as f64 - area).abs() / area < 0.02);
+        assert!((antialiased_coverage(shape) - area).abs() / area < 0.005);
+    }
+
+    #[test]
+    fn it_mutates_shapes_behind_trait_objects() {
+        let mut rng = rng();
+        let mut shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Ellipse::random_rotated(&mut rng, 64, 32)),
+            Box::new(Rectangle::random_rotated(&mut rng, 64, 32)),
+            Box::new(Triangle::random(&mut rng, 64, 32)),
+            Box::new(Polygon::random(&mut rng, 64, 32, 5)),
+        ];
+
+        for shape in shapes.iter_mut() {
+            shape.mutate(&mut rng, 64, 32);
+
+            assert!(shape.is_valid());
+            assert!(!shape.rasterize(64, 32).is_empty());
+        }
+    }
+
+    #[test]
+    fn it_ignores_non_finite_vertices_when_rasterizing() {
+        let polygon = Polygon::from(vec![
+            Point::new(10.0, 10.0),
+            Point::new(f64::NAN, 20.0),
+            Point::new(30.0, 40.0),
+            Point::new(10.0, 40.0),
+        ]);
+
+        assert!(polygon
+            .rasterize(100, 100)
+            .iter()
+            .all(|l| l.x1 <= l.x2 && l.x2 < 100));
+    }
+
+    /// A shape whose spans have non-finite ends.
+    struct Unbounded;
+
+    impl Shape for Unbounded {
+        fn mutate(&mut self, _: &mut dyn rand::RngCore, _: u32, _: u32) {}
+
+        fn spans(&self, _: f64) -> Vec<(f64, f64)> {
+            vec![
+                (f64::NAN, 10.0),
+                (f64::NEG_INFINITY, f64::INFINITY),
+                (20.0, 30.0),
+            ]
+        }
+
+        fn vertical_bounds(&self) -> (f64, f64) {
+            (0.0, 1.0)
+        }
+    }
+
+    #[test]
+    fn it_skips_non_finite_spans_when_rasterizing() {
+        let lines = Unbounded.rasterize(100, 100);
+        assert_eq!(1, lines.len());
+        assert_eq!((20, 29), (lines[0].x1, lines[0].x2));
+
+        let lines = Unbounded.rasterize_antialiased(100, 100);
+        assert_eq!(1, lines.len());
+        assert_eq!((20, 29), (lines[0].x1(), lines[0].x2()));
+    }
+
+    #[test]
+    fn it_picks_random_points_on_an_empty_canvas() {
+        let mut rng = rng();
+
+        let p = random_point(&mut rng, 0, 0);
+        assert_eq!((0.0, 0.0), (p.x, p.y));
+
+        let p = random_point(&mut rng, 8, 0);
+        assert!(p.x >= 0.0 && p.x < 8.0 && p.y == 0.0);
+    }
+}
@@ -1,5 +1,10 @@
 use crate::math::{Point, Vector};
-use super::Shape;
+use super::{
+    clamp_point, jitter, mutate_until_valid, polygon_spans, polygon_vertical_bounds, random_circle,
+    random_point, Shape,
+};
+use rand::{Rng, RngCore};
+use std::f64::consts::PI;
 
 /// Defines a polygon shape thanks to a vector of points defining
 /// its vertices. This shape can be validated by using [`is_valid`]
@@ -33,16 +38,98 @@ use super::Shape;
 ///
 /// [`is_valid`]: ./struct.Polygon.html#method.is_valid
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawPolygon"))]
 pub struct Polygon {
     vertices: Vec<Point>
 }
 
+/// The serialized layout of a polygon, which must have at least 3 vertices
+/// before it's turned into a [`Polygon`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawPolygon {
+    vertices: Vec<Point>,
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<RawPolygon> for Polygon {
+    type Error = String;
+
+    fn try_from(raw: RawPolygon) -> Result<Self, Self::Error> {
+        if raw.vertices.len() < 3 {
+            return Err(format!(
+                "a polygon has at least 3 vertices, found {}",
+                raw.vertices.len()
+            ));
+        }
+
+        Ok(Self {
+            vertices: raw.vertices,
+        })
+    }
+}
+
 impl Polygon {
     /// Instanciates a new polygon shape from a vector of points.
     pub fn new(vertices: Vec<Point>) -> Self {
         Self { vertices }
     }
 
+    /// Instanciates a new random convex polygon of the given order lying inside
+    /// a canvas of the given dimensions, thanks to a user-supplied RNG.
+    ///
+    /// The vertices are spread around a random circle with a small angular
+    /// jitter, which keeps the generated polygon convex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is lesser than 3.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, width: u32, height: u32, order: usize) -> Self {
+        let center = random_point(rng, width, height);
+        Self::random_at(rng, center, width, height, order)
+    }
+
+    /// Instanciates a new random convex polygon of the given order centered on
+    /// the given point of a canvas of the given dimensions, thanks to a
+    /// user-supplied RNG. The center is moved towards the inside of the canvas
+    /// if the polygon would not fit in it otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is lesser than 3.
+    pub fn random_at<R: Rng + ?Sized>(
+        rng: &mut R,
+        center: Point,
+        width: u32,
+        height: u32,
+        order: usize,
+    ) -> Self {
+        assert!(order >= 3, "a polygon has at least 3 vertices");
+
+        let (center, radius) = random_circle(rng, center, width, height);
+        let step = 2.0 * PI / order as f64;
+        let offset = rng.gen_range(0.0..2.0 * PI);
+
+        let vertices = (0..order)
+            .map(|idx| {
+                let angle = offset + step * idx as f64 + rng.gen_range(-step / 4.0..=step / 4.0);
+                Point::new(
+                    center.x + radius * angle.cos(),
+                    center.y + radius * angle.sin(),
+                )
+            })
+            .collect();
+
+        Self { vertices }
+    }
+
+    /// Returns the vertices of the current polygon.
+    #[inline]
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
     /// Returns the order of the current polygon.
     #[inline]
     pub fn order(&self) -> usize {
@@ -95,8 +182,25 @@ impl From<Vec<Point>> for Polygon {
 }
 
 impl Shape for Polygon {
-    fn mutate(&mut self) {
-        //
+    /// Moves a single vertex of the polygon while keeping it convex.
+    fn mutate(&mut self, rng: &mut dyn RngCore, width: u32, height: u32) {
+        if self.vertices.is_empty() {
+            return;
+        }
+
+        mutate_until_valid(self, |polygon| {
+            let idx = rng.gen_range(0..polygon.order());
+            let offset = Vector::new(jitter(rng), jitter(rng));
+            polygon.vertices[idx] = clamp_point(polygon.vertices[idx] + offset, width, height);
+        });
+    }
+
+    fn spans(&self, y: f64) -> Vec<(f64, f64)> {
+        polygon_spans(&self.vertices, y)
+    }
+
+    fn vertical_bounds(&self) -> (f64, f64) {
+        polygon_vertical_bounds(&self.vertices)
     }
 
     /// Checks if the current polygon is valid or not. To do so, the
@@ -135,6 +239,9 @@ impl Shape for Polygon {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::shapes::tests::{
+        antialiased_coverage, assert_area, assert_on_canvas, coverage, rng,
+    };
 
     #[test]
     fn it_validates_a_polygon() {
@@ -160,4 +267,104 @@ mod tests {
 
         assert!(!polygon.is_valid());
     }
+
+    #[test]
+    fn it_generates_valid_random_polygons() {
+        let mut rng = rng();
+        for order in 3..10 {
+            let polygon = Polygon::random(&mut rng, 64, 32, order);
+
+            assert_eq!(order, polygon.order());
+            assert!(polygon.is_valid());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_deserializes_polygons_with_at_least_three_vertices() {
+        let polygon = Polygon::random(&mut rng(), 64, 32, 4);
+        let json = serde_json::to_string(&polygon).unwrap();
+        assert_eq!(4, serde_json::from_str::<Polygon>(&json).unwrap().order());
+
+        let json = r#"{"vertices":[{"x":0.0,"y":0.0},{"x":1.0,"y":1.0}]}"#;
+        assert!(serde_json::from_str::<Polygon>(json).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_requires_at_least_three_vertices() {
+        Polygon::random(&mut rng(), 64, 32, 2);
+    }
+
+    #[test]
+    fn it_rasterizes_a_polygon() {
+        use std::f64::consts::PI;
+
+        // A regular hexagon of circumradius 40
+        let polygon = Polygon::from(
+            (0..6)
+                .map(|idx| {
+                    let angle = idx as f64 * PI / 3.0;
+                    Point::new(50.0 + 40.0 * angle.cos(), 50.0 + 40.0 * angle.sin())
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        assert_area(&polygon, 3.0 * 3_f64.sqrt() / 2.0 * 40.0 * 40.0);
+    }
+
+    #[test]
+    fn it_rasterizes_a_partially_visible_polygon_with_antialiasing() {
+        // A square of side 20 rotated by 45° and centered on the top left corner
+        let polygon = Polygon::from(vec![
+            Point::new(-10.0, -10.0),
+            Point::new(10.0, -10.0),
+            Point::new(10.0, 10.0),
+            Point::new(-10.0, 10.0),
+        ]);
+        let polygon = Polygon::from(
+            polygon
+                .vertices()
+                .iter()
+                .map(|p| {
+                    let (sin, cos) = std::f64::consts::FRAC_PI_4.sin_cos();
+                    Point::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos)
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        assert!((antialiased_coverage(&polygon) - 100.0).abs() / 100.0 < 0.005);
+    }
+
+    #[test]
+    fn it_rasterizes_a_non_convex_polygon() {
+        // A "U" shape: a 30x30 square with a 10x20 notch
+        let polygon = Polygon::from(vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 20.0),
+            Point::new(20.0, 20.0),
+            Point::new(20.0, 0.0),
+            Point::new(30.0, 0.0),
+            Point::new(30.0, 30.0),
+            Point::new(0.0, 30.0),
+        ]);
+
+        let lines = polygon.rasterize(100, 100);
+
+        assert_eq!(700, coverage(&polygon));
+        assert_eq!(2, lines.iter().filter(|l| l.y == 5).count());
+    }
+
+    #[test]
+    fn it_mutates_a_polygon_into_a_valid_one() {
+        let mut rng = rng();
+        let mut polygon = Polygon::random(&mut rng, 64, 32, 5);
+        for _ in 0..1000 {
+            Shape::mutate(&mut polygon, &mut rng, 64, 32);
+
+            assert!(Shape::is_valid(&polygon));
+            assert_on_canvas(&polygon.vertices, 64, 32);
+        }
+    }
 }
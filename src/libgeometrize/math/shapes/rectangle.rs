@@ -1,5 +1,9 @@
-use super::Shape;
-use crate::math::Point;
+use super::{
+    angle_jitter, clamp_point, jitter, mutate_until_valid, polygon_spans, polygon_vertical_bounds,
+    random_point, Shape, RANDOM_EXTENT,
+};
+use crate::math::{Point, Vector};
+use rand::{Rng, RngCore};
 
 const MAX_ASPECT_RATIO: f64 = 5.0;
 
@@ -23,6 +27,7 @@ const MAX_ASPECT_RATIO: f64 = 5.0;
 /// assert!(rect.is_valid());
 /// ```
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rectangle {
     /// The origin of the rectangle.
     pub origin: Point,
@@ -34,10 +39,95 @@ pub struct Rectangle {
 
 impl Rectangle {
     /// Returns a rectangle builder to instanciates a new rectangle.
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> RectangleBuilder {
         RectangleBuilder::default()
     }
 
+    /// Instanciates a new random non-rotated rectangle whose origin lies inside
+    /// a canvas of the given dimensions, thanks to a user-supplied RNG. The
+    /// generated rectangle always respects the maximal aspect ratio.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, width: u32, height: u32) -> Self {
+        Self {
+            scaling: random_scaling(rng),
+            origin: random_point(rng, width, height),
+            angle: 0.0,
+        }
+    }
+
+    /// Instanciates a new random non-rotated rectangle centered on the given
+    /// point of a canvas of the given dimensions, thanks to a user-supplied RNG.
+    pub fn random_at<R: Rng + ?Sized>(rng: &mut R, center: Point, width: u32, height: u32) -> Self {
+        Self::centered(random_scaling(rng), 0.0, center, width, height)
+    }
+
+    /// Instanciates a new random rotated rectangle whose origin lies inside
+    /// a canvas of the given dimensions, thanks to a user-supplied RNG.
+    pub fn random_rotated<R: Rng + ?Sized>(rng: &mut R, width: u32, height: u32) -> Self {
+        let rect = Self::random(rng, width, height);
+
+        Self {
+            angle: rng.gen_range(0.0..std::f64::consts::TAU),
+            ..rect
+        }
+    }
+
+    /// Instanciates a new random rotated rectangle centered on the given point
+    /// of a canvas of the given dimensions, thanks to a user-supplied RNG.
+    pub fn random_rotated_at<R: Rng + ?Sized>(
+        rng: &mut R,
+        center: Point,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let scaling = random_scaling(rng);
+        let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+
+        Self::centered(scaling, angle, center, width, height)
+    }
+
+    /// Instanciates a rectangle whose center is the given point, once clamped
+    /// to a canvas of the given dimensions.
+    fn centered(scaling: (f64, f64), angle: f64, center: Point, width: u32, height: u32) -> Self {
+        let center = clamp_point(center, width, height);
+        let (sin, cos) = angle.sin_cos();
+        let (half_width, half_height) = (scaling.0 / 2.0, scaling.1 / 2.0);
+
+        Self {
+            origin: Point::new(
+                center.x - half_width * cos + half_height * sin,
+                center.y - half_width * sin - half_height * cos,
+            ),
+            scaling,
+            angle,
+        }
+    }
+
+    /// Indicates if the current rectangle is rotated or not.
+    pub fn is_rotated(&self) -> bool {
+        self.angle != 0.0
+    }
+
+    /// Returns the four corners of the rectangle, obtained by scaling the unit
+    /// square, rotating it around the origin and translating it to the origin.
+    pub fn vertices(&self) -> [Point; 4] {
+        let (sin, cos) = self.angle.sin_cos();
+        let corner = |u: f64, v: f64| {
+            let (x, y) = (u * self.scaling.0, v * self.scaling.1);
+            Point::new(
+                self.origin.x + x * cos - y * sin,
+                self.origin.y + x * sin + y * cos,
+            )
+        };
+
+        [
+            corner(0.0, 0.0),
+            corner(1.0, 0.0),
+            corner(1.0, 1.0),
+            corner(0.0, 1.0),
+        ]
+    }
+
     /// Returns the width of the rectangle.
     pub const fn width(&self) -> f64 {
         self.scaling.0
@@ -49,6 +139,16 @@ impl Rectangle {
     }
 }
 
+/// Picks a random scaling for a rectangle which respects the maximal aspect
+/// ratio.
+fn random_scaling<R: Rng + ?Sized>(rng: &mut R) -> (f64, f64) {
+    let w = rng.gen_range(1.0..=RANDOM_EXTENT);
+    let h =
+        rng.gen_range((w / MAX_ASPECT_RATIO).max(1.0)..=(w * MAX_ASPECT_RATIO).min(RANDOM_EXTENT));
+
+    (w, h)
+}
+
 impl Default for Rectangle {
     fn default() -> Self {
         Self {
@@ -60,8 +160,32 @@ impl Default for Rectangle {
 }
 
 impl Shape for Rectangle {
-    fn mutate(&mut self) {
-        //
+    /// Mutates either the origin, the scaling or, for a rotated rectangle,
+    /// the rotation angle. The maximal aspect ratio is always respected.
+    fn mutate(&mut self, rng: &mut dyn RngCore, width: u32, height: u32) {
+        let parameters = if self.is_rotated() { 3 } else { 2 };
+
+        mutate_until_valid(self, |rect| match rng.gen_range(0..parameters) {
+            0 => {
+                let offset = Vector::new(jitter(rng), jitter(rng));
+                rect.origin = clamp_point(rect.origin + offset, width, height);
+            }
+            1 => {
+                rect.scaling = (
+                    (rect.scaling.0 + jitter(rng)).clamp(1.0, width.max(1) as f64),
+                    (rect.scaling.1 + jitter(rng)).clamp(1.0, height.max(1) as f64),
+                )
+            }
+            _ => rect.angle += angle_jitter(rng),
+        });
+    }
+
+    fn spans(&self, y: f64) -> Vec<(f64, f64)> {
+        polygon_spans(&self.vertices(), y)
+    }
+
+    fn vertical_bounds(&self) -> (f64, f64) {
+        polygon_vertical_bounds(&self.vertices())
     }
 
     fn is_valid(&self) -> bool {
@@ -136,6 +260,7 @@ impl Default for RectangleBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::shapes::tests::{assert_area, rng};
 
     #[test]
     fn it_validates_a_rectangle() {
@@ -150,4 +275,70 @@ mod tests {
 
         assert!(!r.is_valid());
     }
+
+    #[test]
+    fn it_generates_valid_random_rectangles() {
+        let mut rng = rng();
+        for _ in 0..100 {
+            let r = Rectangle::random_rotated(&mut rng, 64, 32);
+
+            assert!(r.is_valid());
+            assert!(r.origin.x >= 0.0 && r.origin.x < 64.0);
+            assert!(r.origin.y >= 0.0 && r.origin.y < 32.0);
+        }
+    }
+
+    #[test]
+    fn it_generates_a_random_rectangle_around_a_center() {
+        let mut rng = rng();
+        let r = Rectangle::random_rotated_at(&mut rng, Point::new(20.0, 10.0), 64, 32);
+        let vertices = r.vertices();
+        let x = vertices.iter().map(|p| p.x).sum::<f64>() / 4.0;
+        let y = vertices.iter().map(|p| p.y).sum::<f64>() / 4.0;
+
+        assert!(r.is_valid() && r.is_rotated());
+        assert!((x - 20.0).abs() < 1e-9 && (y - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_mutates_a_rectangle_into_a_valid_one() {
+        let mut rng = rng();
+        let mut r = Rectangle::random_rotated(&mut rng, 64, 32);
+        for _ in 0..1000 {
+            r.mutate(&mut rng, 64, 32);
+
+            assert!(r.is_valid());
+            assert!(r.origin.x >= 0.0 && r.origin.x <= 64.0);
+            assert!(r.origin.y >= 0.0 && r.origin.y <= 32.0);
+            assert!(r.width() <= 64.0 && r.height() <= 32.0);
+        }
+    }
+
+    #[test]
+    fn it_rasterizes_a_rotated_rectangle() {
+        let r = Rectangle::new()
+            .origin(50.0, 20.0)
+            .aspect(40.0, 25.0)
+            .angle(std::f64::consts::FRAC_PI_3)
+            .build();
+
+        assert_area(&r, 1000.0);
+    }
+
+    #[test]
+    fn it_rasterizes_an_axis_aligned_rectangle_exactly() {
+        let r = Rectangle::new()
+            .origin(10.0, 20.0)
+            .aspect(30.0, 5.0)
+            .build();
+
+        let lines = r.rasterize(100, 100);
+
+        assert_eq!(5, lines.len());
+        assert!(lines.iter().all(|l| l.x1 == 10 && l.x2 == 39));
+        assert_eq!(
+            (20..25).collect::<Vec<_>>(),
+            lines.iter().map(|l| l.y).collect::<Vec<_>>()
+        );
+    }
 }
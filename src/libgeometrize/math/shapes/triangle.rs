@@ -1,5 +1,10 @@
-use super::Shape;
+use super::{
+    clamp_point, jitter, mutate_until_valid, polygon_spans, polygon_vertical_bounds, random_circle,
+    random_point, Shape,
+};
 use crate::math::{Point, Vector};
+use rand::{Rng, RngCore};
+use std::f64::consts::PI;
 
 /// Defines a triangle with a vector of 3 vertices which are 3 points on the
 /// the plane.
@@ -8,6 +13,7 @@ use crate::math::{Point, Vector};
 /// are greater than 15°. If not, the triangle is considered as invalid though
 /// its still a triangle.
 #[derive(Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Triangle {
     vertices: [Point; 3],
 }
@@ -23,7 +29,43 @@ impl Triangle {
         }
     }
 
-    // TODO: add a method to instanciate a random triangle
+    /// Returns the vertices of the current triangle.
+    #[inline]
+    pub const fn vertices(&self) -> &[Point; 3] {
+        &self.vertices
+    }
+
+    /// Instanciates a new random triangle lying inside a canvas of the given
+    /// dimensions, thanks to a user-supplied RNG.
+    ///
+    /// The vertices are picked on a random circle and the arcs between them are
+    /// kept between 60° and 180°, so the inner angles of the triangle lie between
+    /// 30° and 90° and the generated triangle is always valid.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, width: u32, height: u32) -> Self {
+        let center = random_point(rng, width, height);
+        Self::random_at(rng, center, width, height)
+    }
+
+    /// Instanciates a new random valid triangle centered on the given point of
+    /// a canvas of the given dimensions, thanks to a user-supplied RNG. The
+    /// center is moved towards the inside of the canvas if the triangle would
+    /// not fit in it otherwise.
+    pub fn random_at<R: Rng + ?Sized>(rng: &mut R, center: Point, width: u32, height: u32) -> Self {
+        let (center, radius) = random_circle(rng, center, width, height);
+
+        let first = rng.gen_range(0.0..2.0 * PI);
+        let second = first + rng.gen_range(PI / 2.0..=5.0 * PI / 6.0);
+        let third = second + rng.gen_range(PI / 2.0..=5.0 * PI / 6.0);
+
+        let vertex = |angle: f64| {
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        };
+
+        Self::new(vertex(first), vertex(second), vertex(third))
+    }
 }
 
 impl From<[Point; 3]> for Triangle {
@@ -33,8 +75,13 @@ impl From<[Point; 3]> for Triangle {
 }
 
 impl Shape for Triangle {
-    fn mutate(&mut self) {
-        //
+    /// Moves a single vertex of the triangle.
+    fn mutate(&mut self, rng: &mut dyn RngCore, width: u32, height: u32) {
+        mutate_until_valid(self, |triangle| {
+            let idx = rng.gen_range(0..3);
+            let offset = Vector::new(jitter(rng), jitter(rng));
+            triangle.vertices[idx] = clamp_point(triangle.vertices[idx] + offset, width, height);
+        });
     }
 
     fn is_valid(&self) -> bool {
@@ -52,11 +99,20 @@ impl Shape for Triangle {
 
         a1 >= MIN_INTERNAL_ANGLE && a2 >= MIN_INTERNAL_ANGLE && a3 >= MIN_INTERNAL_ANGLE
     }
+
+    fn spans(&self, y: f64) -> Vec<(f64, f64)> {
+        polygon_spans(&self.vertices, y)
+    }
+
+    fn vertical_bounds(&self) -> (f64, f64) {
+        polygon_vertical_bounds(&self.vertices)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::shapes::tests::{assert_area, assert_on_canvas, rng};
 
     #[test]
     fn it_validate_a_triangle() {
@@ -70,4 +126,42 @@ mod tests {
         assert!(a.is_valid());
         assert!(!b.is_valid());
     }
+
+    #[test]
+    fn it_generates_valid_random_triangles() {
+        let mut rng = rng();
+        for _ in 0..100 {
+            let t = Triangle::random(&mut rng, 64, 32);
+
+            assert!(t.is_valid());
+            assert_on_canvas(&t.vertices, 64, 32);
+        }
+    }
+
+    #[test]
+    fn it_rasterizes_a_triangle() {
+        let t = Triangle::new(
+            Point::new(10.2, 10.7),
+            Point::new(90.4, 30.1),
+            Point::new(40.9, 80.3),
+        );
+
+        // Area given by the shoelace formula
+        assert_area(
+            &t,
+            0.5 * ((90.4 - 10.2) * (80.3 - 10.7) - (40.9 - 10.2) * (30.1 - 10.7)),
+        );
+    }
+
+    #[test]
+    fn it_mutates_a_triangle_into_a_valid_one() {
+        let mut rng = rng();
+        let mut t = Triangle::random(&mut rng, 64, 32);
+        for _ in 0..1000 {
+            t.mutate(&mut rng, 64, 32);
+
+            assert!(t.is_valid());
+            assert_on_canvas(&t.vertices, 64, 32);
+        }
+    }
 }
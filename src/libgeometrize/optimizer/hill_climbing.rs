@@ -0,0 +1,240 @@
+use super::Candidate;
+use crate::images::{difference_partial, optimal_color, Canvas};
+use crate::math::{shapes::Shape, Coverage, Rasterizer};
+use image::{Rgba, RgbaImage};
+use rand::Rng;
+
+/// Defines a hill climbing optimizer searching the shape that reduces the most
+/// the difference between a target image and the current canvas.
+///
+/// The optimizer starts by generating `candidates` random shapes and keeps the
+/// best one. This shape is then mutated up to `mutations` times, each mutation
+/// being kept only if it improves the score.
+///
+/// # Example
+///
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use libgeometrize::images::{difference_full, Canvas};
+/// use libgeometrize::math::shapes::Ellipse;
+/// use libgeometrize::optimizer::HillClimbing;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let target = RgbaImage::from_fn(32, 32, |x, _| Rgba([x as u8 * 8, 0, 0, 255]));
+/// let canvas = Canvas::new(32, 32, Rgba([0, 0, 0, 255]));
+/// let score = difference_full(&target, &canvas);
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let candidate = HillClimbing::default().run(&target, &canvas, score, &mut rng, Ellipse::random);
+///
+/// assert!(candidate.score < score);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HillClimbing {
+    /// The number of random shapes generated before the mutations.
+    pub candidates: u32,
+
+    /// The maximal number of mutations applied to the best random shape.
+    pub mutations: u32,
+
+    /// The opacity of the colors of the shapes.
+    pub alpha: u8,
+
+    /// The rasterization method used to draw and score the shapes.
+    pub rasterizer: Rasterizer,
+}
+
+impl Default for HillClimbing {
+    fn default() -> Self {
+        Self {
+            candidates: 100,
+            mutations: 100,
+            alpha: 128,
+            rasterizer: Rasterizer::Aliased,
+        }
+    }
+}
+
+impl HillClimbing {
+    /// Searches the best shape to draw on the current canvas to get closer to
+    /// the target image, knowing their current difference `score`. The random
+    /// shapes are produced by `generate`, which receives the RNG and the
+    /// dimensions of the canvas.
+    ///
+    /// Invalid shapes are never selected, unless no valid shape has been
+    /// generated at all in which case the last generated one is kept.
+    pub fn run<S, R, F>(
+        &self,
+        target: &RgbaImage,
+        current: &Canvas,
+        score: f64,
+        rng: &mut R,
+        mut generate: F,
+    ) -> Candidate<S>
+    where
+        S: Shape + Clone,
+        R: Rng + ?Sized,
+        F: FnMut(&mut R, u32, u32) -> S,
+    {
+        let (width, height) = current.dimensions();
+        let mut buffer = current.clone();
+
+        let mut best: Option<Candidate<S>> = None;
+        let mut last = None;
+        for _ in 0..self.candidates {
+            let shape = generate(rng, width, height);
+            if !shape.is_valid() {
+                last = Some(shape);
+                continue;
+            }
+
+            let candidate = self.evaluate(shape, target, current, &mut buffer, score);
+            // `Option::is_none_or` would read better, but requires Rust 1.82
+            #[allow(clippy::unnecessary_map_or)]
+            let improves = best
+                .as_ref()
+                .map_or(true, |best| candidate.score < best.score);
+            if improves {
+                best = Some(candidate);
+            }
+        }
+
+        let mut best = best.unwrap_or_else(|| {
+            let shape = last.unwrap_or_else(|| generate(rng, width, height));
+            self.evaluate(shape, target, current, &mut buffer, score)
+        });
+
+        for _ in 0..self.mutations {
+            let mut shape = best.shape.clone();
+            // The RNG may be unsized, so the shape gets it through a reference
+            shape.mutate(&mut &mut *rng, width, height);
+            if !shape.is_valid() {
+                continue;
+            }
+
+            let candidate = self.evaluate(shape, target, current, &mut buffer, score);
+            if candidate.score < best.score {
+                best = candidate;
+            }
+        }
+
+        best
+    }
+
+    /// Computes the color and the score of a shape. The buffer must be a copy
+    /// of the current canvas and is left untouched once the shape is scored.
+    fn evaluate<S: Shape>(
+        &self,
+        shape: S,
+        target: &RgbaImage,
+        current: &Canvas,
+        buffer: &mut Canvas,
+        score: f64,
+    ) -> Candidate<S> {
+        let (width, height) = current.dimensions();
+
+        let (color, score) = match self.rasterizer {
+            Rasterizer::Aliased => {
+                let lines = shape.rasterize(width, height);
+                self.energy(&lines, target, current, buffer, score)
+            }
+            Rasterizer::Antialiased => {
+                let lines = shape.rasterize_antialiased(width, height);
+                self.energy(&lines, target, current, buffer, score)
+            }
+        };
+
+        Candidate {
+            shape,
+            color,
+            score,
+        }
+    }
+
+    /// Computes the optimal color of the given scanlines and the score reached
+    /// once they're drawn with it.
+    fn energy<L: Coverage>(
+        &self,
+        lines: &[L],
+        target: &RgbaImage,
+        current: &Canvas,
+        buffer: &mut Canvas,
+        score: f64,
+    ) -> (Rgba<u8>, f64) {
+        let color = optimal_color(target, current, lines, self.alpha);
+
+        buffer.draw_lines(lines, color);
+        let score = difference_partial(target, current, buffer, score, lines);
+        buffer.copy_lines(current, lines);
+
+        (color, score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::difference_full;
+    use crate::math::shapes::{Rectangle, Triangle};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn target() -> RgbaImage {
+        RgbaImage::from_fn(48, 32, |x, y| {
+            if (10..30).contains(&x) && (8..20).contains(&y) {
+                Rgba([200, 30, 30, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn it_finds_a_shape_improving_the_score() {
+        let target = target();
+        let mut canvas = Canvas::new(48, 32, Rgba([255, 255, 255, 255]));
+        let score = difference_full(&target, &canvas);
+
+        let optimizer = HillClimbing {
+            alpha: 255,
+            ..HillClimbing::default()
+        };
+        let mut rng = StdRng::seed_from_u64(42);
+        let candidate = optimizer.run(&target, &canvas, score, &mut rng, Rectangle::random);
+
+        assert!(candidate.shape.is_valid());
+        assert!(candidate.score < score);
+
+        canvas.draw(&candidate.shape, candidate.color, optimizer.rasterizer);
+        assert!((candidate.score - difference_full(&target, &canvas)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_is_reproducible_from_a_seed() {
+        let target = target();
+        let canvas = Canvas::new(48, 32, Rgba([255, 255, 255, 255]));
+        let score = difference_full(&target, &canvas);
+
+        let optimizer = HillClimbing {
+            rasterizer: Rasterizer::Antialiased,
+            ..HillClimbing::default()
+        };
+        let a = optimizer.run(
+            &target,
+            &canvas,
+            score,
+            &mut StdRng::seed_from_u64(7),
+            Triangle::random,
+        );
+        let b = optimizer.run(
+            &target,
+            &canvas,
+            score,
+            &mut StdRng::seed_from_u64(7),
+            Triangle::random,
+        );
+
+        assert_eq!(a.color, b.color);
+        assert_eq!(a.score, b.score);
+        assert!(a.score < score);
+    }
+}
@@ -0,0 +1,17 @@
+mod hill_climbing;
+mod model;
+
+use image::Rgba;
+
+/// Defines a shape selected by an optimizer, along with the color it must be
+/// drawn with and the difference between the target image and the canvas once
+/// the shape is drawn on it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate<S> {
+    pub shape: S,
+    pub color: Rgba<u8>,
+    pub score: f64,
+}
+
+pub use hill_climbing::HillClimbing;
+pub use model::Model;
@@ -0,0 +1,231 @@
+use super::{Candidate, HillClimbing};
+use crate::images::{average_color, difference_full, Canvas, HeatmapSampler};
+use crate::math::shapes::{AnyShape, ShapeKind};
+use crate::math::Point;
+use image::{Rgba, RgbaImage};
+use rand::{seq::SliceRandom, Rng};
+
+/// A model approximates a target image with shapes, added one at a time on
+/// a canvas. It keeps track of the current difference between the target image
+/// and the canvas, as well as of the history of the placed shapes.
+///
+/// # Example
+///
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use libgeometrize::math::shapes::ShapeKind;
+/// use libgeometrize::optimizer::{HillClimbing, Model};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let target = RgbaImage::from_fn(32, 32, |x, y| Rgba([x as u8 * 8, y as u8 * 8, 0, 255]));
+/// let mut model = Model::new(target);
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let kinds = [ShapeKind::RotatedEllipse, ShapeKind::Triangle];
+/// for _ in 0..10 {
+///     model.step(&mut rng, &kinds, &HillClimbing::default());
+/// }
+///
+/// assert_eq!(10, model.shapes().len());
+/// let image = model.current().to_rgba8();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Model {
+    target: RgbaImage,
+    current: Canvas,
+    background: Rgba<u8>,
+    score: f64,
+    shapes: Vec<Candidate<AnyShape>>,
+    importance: Option<HeatmapSampler>,
+}
+
+impl Model {
+    /// Instanciates a new model approximating the target image, starting from
+    /// a canvas filled with the average color of the target image.
+    pub fn new(target: RgbaImage) -> Self {
+        let background = average_color(&target);
+        Self::with_background(target, background)
+    }
+
+    /// Instanciates a new model approximating the target image, starting from
+    /// a canvas filled with the given background color.
+    pub fn with_background(target: RgbaImage, background: Rgba<u8>) -> Self {
+        let current = Canvas::new(target.width(), target.height(), background);
+        let score = difference_full(&target, &current);
+
+        Self {
+            target,
+            current,
+            background,
+            score,
+            shapes: Vec::new(),
+            importance: None,
+        }
+    }
+
+    /// Defines the sampler of the heatmap used to place the centers of the
+    /// random shapes, or places them uniformly on the canvas if there is none.
+    /// The heatmap is stretched to the dimensions of the canvas, so it can
+    /// have a lower resolution than the target image.
+    pub fn set_importance(&mut self, importance: Option<HeatmapSampler>) {
+        self.importance = importance;
+    }
+
+    /// Runs a single optimization round and draws the winning shape on the
+    /// canvas. Each random shape is of a kind picked among `kinds`, and the
+    /// search is driven by the given optimizer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `kinds` is empty.
+    pub fn step<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        kinds: &[ShapeKind],
+        optimizer: &HillClimbing,
+    ) -> &Candidate<AnyShape> {
+        assert!(!kinds.is_empty(), "at least one kind of shape is required");
+
+        let importance = self.importance.as_ref();
+        let candidate = optimizer.run(
+            &self.target,
+            &self.current,
+            self.score,
+            rng,
+            |rng, width, height| {
+                let kind = kinds.choose(rng).unwrap();
+
+                match importance {
+                    Some(sampler) => {
+                        let (sampled_width, sampled_height) = sampler.dimensions();
+                        let p = sampler.sample_point(rng);
+                        let center = Point::new(
+                            p.x * width as f64 / sampled_width as f64,
+                            p.y * height as f64 / sampled_height as f64,
+                        );
+
+                        kind.random_at(rng, center, width, height)
+                    }
+                    None => kind.random(rng, width, height),
+                }
+            },
+        );
+
+        self.current
+            .draw(&candidate.shape, candidate.color, optimizer.rasterizer);
+        self.score = candidate.score;
+        self.shapes.push(candidate);
+
+        self.shapes.last().unwrap()
+    }
+
+    /// Returns the target image approximated by the model.
+    #[inline]
+    pub fn target(&self) -> &RgbaImage {
+        &self.target
+    }
+
+    /// Returns the canvas on which the shapes are drawn.
+    #[inline]
+    pub fn current(&self) -> &Canvas {
+        &self.current
+    }
+
+    /// Returns the background color of the canvas.
+    #[inline]
+    pub fn background(&self) -> Rgba<u8> {
+        self.background
+    }
+
+    /// Returns the current difference between the target image and the canvas.
+    #[inline]
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Returns the shapes placed on the canvas, in the order they were drawn.
+    #[inline]
+    pub fn shapes(&self) -> &[Candidate<AnyShape>] {
+        &self.shapes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn it_accumulates_shapes_step_by_step() {
+        let target = RgbaImage::from_fn(48, 32, |x, y| {
+            Rgba([(x * 5) as u8, (y * 7) as u8, ((x + y) * 3) as u8, 255])
+        });
+        let mut model = Model::new(target);
+        let initial_score = model.score();
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let kinds = [
+            ShapeKind::RotatedEllipse,
+            ShapeKind::RotatedRectangle,
+            ShapeKind::Triangle,
+            ShapeKind::Polygon(5),
+        ];
+        let optimizer = HillClimbing {
+            candidates: 20,
+            mutations: 40,
+            ..HillClimbing::default()
+        };
+        for _ in 0..8 {
+            model.step(&mut rng, &kinds, &optimizer);
+        }
+
+        assert_eq!(8, model.shapes().len());
+        assert!(model.score() < initial_score);
+        assert_eq!(model.shapes().last().unwrap().score, model.score());
+        assert!((model.score() - difference_full(model.target(), model.current())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_places_shapes_where_the_heatmap_is_hot() {
+        use crate::images::Heatmap;
+
+        let target = RgbaImage::from_fn(64, 64, |x, y| Rgba([x as u8 * 4, y as u8 * 4, 0, 255]));
+        let mut model = Model::new(target);
+
+        // The heatmap has a lower resolution than the canvas
+        let heatmap: Heatmap = Heatmap::from_fn(8, 8, |x, y| if (x, y) == (2, 5) { 1 } else { 0 });
+        model.set_importance(Some(HeatmapSampler::new(&heatmap)));
+
+        let optimizer = HillClimbing {
+            candidates: 10,
+            mutations: 0,
+            ..HillClimbing::default()
+        };
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..5 {
+            match &model
+                .step(&mut rng, &[ShapeKind::Ellipse], &optimizer)
+                .shape
+            {
+                AnyShape::Ellipse(ellipse) => {
+                    let center = ellipse.center();
+                    assert!(center.x >= 16.0 && center.x < 24.0);
+                    assert!(center.y >= 40.0 && center.y < 48.0);
+                }
+                shape => panic!("unexpected shape {:?}", shape),
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_requires_a_kind_of_shape() {
+        let mut model = Model::new(RgbaImage::new(8, 8));
+
+        model.step(
+            &mut StdRng::seed_from_u64(42),
+            &[],
+            &HillClimbing::default(),
+        );
+    }
+}
@@ -1,3 +1,189 @@
+use image::{DynamicImage, ImageFormat};
+#[cfg(feature = "json")]
+use libgeometrize::formats::to_json;
+use libgeometrize::formats::to_svg;
+use libgeometrize::math::{shapes::ShapeKind, Rasterizer};
+use libgeometrize::optimizer::{HillClimbing, Model};
+use rand::{rngs::StdRng, SeedableRng};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// Approximates an image with geometric shapes.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "geometrize")]
+struct Options {
+    /// The path of the image to approximate
+    #[structopt(short, long, parse(from_os_str))]
+    input: PathBuf,
+
+    /// The paths of the produced approximations, whose format is inferred
+    /// from their extension (PNG, JPEG, ... SVG or JSON)
+    #[structopt(short, long, parse(from_os_str), required = true)]
+    output: Vec<PathBuf>,
+
+    /// The number of shapes to place
+    #[structopt(short = "n", long, default_value = "100")]
+    shapes: u32,
+
+    /// The kinds of shapes to use: ellipse, rotated-ellipse, rectangle,
+    /// rotated-rectangle, triangle or polygon
+    #[structopt(short = "t", long = "types", default_value = "triangle", parse(try_from_str = parse_kind))]
+    kinds: Vec<Kind>,
+
+    /// The order of the polygons, when polygons are used
+    #[structopt(long, default_value = "5")]
+    polygon_order: usize,
+
+    /// The opacity of the shapes
+    #[structopt(short, long, default_value = "128")]
+    alpha: u8,
+
+    /// The number of random shapes generated at each step
+    #[structopt(short, long, default_value = "100")]
+    candidates: u32,
+
+    /// The number of mutations tried at each step
+    #[structopt(short, long, default_value = "100")]
+    mutations: u32,
+
+    /// The seed of the random number generator, a random one is used if none
+    /// is provided
+    #[structopt(short, long)]
+    seed: Option<u64>,
+
+    /// Rasterizes the shapes with antialiasing
+    #[structopt(long)]
+    antialiased: bool,
+}
+
+/// Defines a kind of shape given on the command line. Polygons don't carry
+/// their order, which is given by a dedicated option.
+#[derive(Clone, Copy, Debug)]
+enum Kind {
+    Shape(ShapeKind),
+    Polygon,
+}
+
+fn parse_kind(kind: &str) -> Result<Kind, String> {
+    match kind {
+        "ellipse" => Ok(Kind::Shape(ShapeKind::Ellipse)),
+        "rotated-ellipse" => Ok(Kind::Shape(ShapeKind::RotatedEllipse)),
+        "rectangle" => Ok(Kind::Shape(ShapeKind::Rectangle)),
+        "rotated-rectangle" => Ok(Kind::Shape(ShapeKind::RotatedRectangle)),
+        "triangle" => Ok(Kind::Shape(ShapeKind::Triangle)),
+        "polygon" => Ok(Kind::Polygon),
+        _ => Err(format!("unknown shape type `{}`", kind)),
+    }
+}
+
+/// Defines the format of an output file.
+#[derive(Clone, Copy, Debug)]
+enum OutputFormat {
+    Raster(ImageFormat),
+    Svg,
+    #[cfg(feature = "json")]
+    Json,
+}
+
+impl OutputFormat {
+    /// Infers the format of an output file from its extension.
+    fn from_path(path: &Path) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("svg") => Ok(OutputFormat::Svg),
+            #[cfg(feature = "json")]
+            Some("json") => Ok(OutputFormat::Json),
+            #[cfg(not(feature = "json"))]
+            Some("json") => Err("JSON output requires the `json` feature".to_string()),
+            _ => ImageFormat::from_path(path)
+                .map(OutputFormat::Raster)
+                .map_err(|_| format!("unsupported output format for `{}`", path.display())),
+        }
+    }
+}
+
+fn run(options: Options) -> Result<(), Box<dyn Error>> {
+    if options.polygon_order < 3 {
+        return Err("the order of the polygons must be at least 3".into());
+    }
+
+    let outputs = options
+        .output
+        .iter()
+        .map(|path| OutputFormat::from_path(path).map(|format| (path, format)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let kinds: Vec<ShapeKind> = options
+        .kinds
+        .iter()
+        .map(|kind| match kind {
+            Kind::Shape(kind) => *kind,
+            Kind::Polygon => ShapeKind::Polygon(options.polygon_order),
+        })
+        .collect();
+
+    let optimizer = HillClimbing {
+        candidates: options.candidates,
+        mutations: options.mutations,
+        alpha: options.alpha,
+        rasterizer: if options.antialiased {
+            Rasterizer::Antialiased
+        } else {
+            Rasterizer::Aliased
+        },
+    };
+
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let target = image::open(&options.input)?.to_rgba8();
+    let mut model = Model::new(target);
+    for _ in 0..options.shapes {
+        model.step(&mut rng, &kinds, &optimizer);
+    }
+
+    for (path, format) in outputs {
+        match format {
+            OutputFormat::Raster(format) => {
+                let image = model.current().to_rgba8();
+                if format == ImageFormat::Jpeg {
+                    // JPEG doesn't support transparency
+                    DynamicImage::ImageRgba8(image)
+                        .to_rgb8()
+                        .save_with_format(path, format)?;
+                } else {
+                    image.save_with_format(path, format)?;
+                }
+            }
+            OutputFormat::Svg => {
+                let (width, height) = model.current().dimensions();
+                let shapes = model.shapes().iter().map(|c| (&c.shape, c.color));
+                std::fs::write(path, to_svg(width, height, model.background(), shapes))?;
+            }
+            #[cfg(feature = "json")]
+            OutputFormat::Json => std::fs::write(path, to_json(model.shapes()))?,
+        }
+    }
+
+    eprintln!(
+        "placed {} shapes with a final score of {:.6}",
+        model.shapes().len(),
+        model.score()
+    );
+
+    Ok(())
+}
+
 fn main() {
-    println!("Hello, world!");
+    if let Err(error) = run(Options::from_args()) {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
 }